@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use tf_bindgen::schema::provider::Schema;
+use tf_bindgen::Builder;
+
+/// Generate Rust bindings for Terraform providers.
+#[derive(Parser)]
+#[command(name = "tf-bindgen", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate bindings and write them to a directory.
+    Generate {
+        #[command(flatten)]
+        source: SchemaArgs,
+        /// Directory to write the generated bindings to.
+        #[arg(long, default_value = "src")]
+        out: PathBuf,
+        /// Name of the generated root module file (relative to `--out`).
+        #[arg(long, default_value = "terraform.rs")]
+        root_file: PathBuf,
+        /// Write all bindings into the root module file instead of one file per resource.
+        #[arg(long)]
+        single_file: bool,
+        /// Gate every resource and data source module behind a cargo feature. The matching
+        /// `[features]` table is written to `features.toml`.
+        #[arg(long)]
+        feature_gates: bool,
+    },
+    /// Inspect provider schemas.
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommand,
+    },
+    /// List all resources and data sources of the configured providers.
+    ListResources {
+        #[command(flatten)]
+        source: SchemaArgs,
+    },
+    /// Convert an existing Terraform configuration into Rust code using the generated
+    /// bindings.
+    Import {
+        #[command(flatten)]
+        source: SchemaArgs,
+        /// Configuration file (`.tf` or `.tf.json`) or directory to convert.
+        path: PathBuf,
+        /// Write the generated code to this file instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Path of the module containing the generated bindings.
+        #[arg(long, default_value = "crate::terraform")]
+        bindings_module: String,
+        /// Name of the generated function.
+        #[arg(long, default_value = "build")]
+        function_name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SchemaCommand {
+    /// Print the provider schema as JSON.
+    Dump {
+        #[command(flatten)]
+        source: SchemaArgs,
+        /// Write the schema to this file instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+struct SchemaArgs {
+    /// Path to the configuration file.
+    #[arg(long, default_value = "terraform.toml")]
+    config: String,
+    /// Read the provider schema from this file instead of invoking Terraform.
+    #[arg(long)]
+    schema: Option<PathBuf>,
+    /// Directory used to cache provider schemas.
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+    /// Do not use cached provider schemas.
+    #[arg(long)]
+    no_cache: bool,
+}
+
+impl SchemaArgs {
+    fn builder(self) -> Builder {
+        let mut builder = Builder::default();
+        builder.config(self.config);
+        if let Some(schema) = self.schema {
+            builder.schema_file(schema);
+        }
+        if let Some(cache_dir) = self.cache_dir {
+            builder.cache_dir(cache_dir);
+        }
+        if self.no_cache {
+            builder.disable_cache();
+        }
+        builder
+    }
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Generate {
+            source,
+            out,
+            root_file,
+            single_file,
+            feature_gates,
+        } => {
+            let mut builder = source.builder();
+            if feature_gates {
+                builder.feature_gates();
+            }
+            let bindings = builder.generate()?;
+            let report = if single_file {
+                bindings.write_to_single_file(out.join(root_file))
+            } else {
+                bindings.write_to_file(&out, root_file)
+            }
+            .with_context(|| format!("failed to write bindings to {}", out.display()))?;
+            print!("{report}");
+        }
+        Command::Schema {
+            command: SchemaCommand::Dump { source, out },
+        } => {
+            let bindings = source.builder().generate()?;
+            let schema = serde_json::to_string_pretty(bindings.schema())
+                .context("failed to serialize provider schema")?;
+            match out {
+                Some(path) => std::fs::write(&path, schema)
+                    .with_context(|| format!("failed to write schema to {}", path.display()))?,
+                None => println!("{schema}"),
+            }
+        }
+        Command::ListResources { source } => {
+            let bindings = source.builder().generate()?;
+            let provider_schemas = match bindings.schema() {
+                Schema::V1_0 { provider_schemas } => provider_schemas,
+                Schema::Unknown => bail!("unsupported provider schema version"),
+            };
+            for (url, schema) in provider_schemas {
+                println!("{url}");
+                for name in schema.resource_schemas.keys() {
+                    println!("  resource {name}");
+                }
+                for name in schema.data_source_schemas.keys() {
+                    println!("  data {name}");
+                }
+            }
+        }
+        Command::Import {
+            source,
+            path,
+            out,
+            bindings_module,
+            function_name,
+        } => {
+            let bindings = source.builder().generate()?;
+            let code = bindings
+                .importer()
+                .bindings_module(bindings_module)
+                .function_name(function_name)
+                .import_path(&path)?;
+            match out {
+                Some(out) => std::fs::write(&out, code)
+                    .with_context(|| format!("failed to write code to {}", out.display()))?,
+                None => print!("{code}"),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,651 @@
+//! Used to render a Terraform JSON document (see [`Document`]) using Terraform's native syntax.
+//!
+//! Terraform decides whether a JSON object is a block or an attribute using the provider schema.
+//! Generated resources and data sources record the paths of their nested blocks (see
+//! [`ResourceMetadata::blocks`]), which are used instead. If missing (e.g. for providers), a list of objects is written as repeated nested blocks and an object is written
+//! as an object expression (e.g. `labels = { ... }`). The `lifecycle` meta-argument and
+//! `required_providers` are always written as blocks.
+//!
+//! [`ResourceMetadata::blocks`]: tf_bindgen_schema::document::ResourceMetadata::blocks
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+use tf_bindgen_schema::document::Document;
+
+/// Meta-arguments written before the other attributes of a resource.
+const LEADING: &[&str] = &["count", "for_each", "provider"];
+
+/// Meta-arguments written after the other attributes and blocks of a resource.
+const TRAILING: &[&str] = &["depends_on", "lifecycle"];
+
+/// Paths of the meta-arguments of resources and data sources whose values are references or
+/// keywords and must be written without quotes.
+const TRAVERSALS: &[&str] = &[
+    "provider",
+    "depends_on",
+    "lifecycle.ignore_changes",
+    "lifecycle.replace_triggered_by",
+];
+
+/// Paths of the meta-arguments of outputs written without quotes (see [`TRAVERSALS`]).
+const OUTPUT_TRAVERSALS: &[&str] = &["depends_on"];
+
+/// Objects always written as blocks.
+const BLOCKS: &[&str] = &["lifecycle", "required_providers"];
+
+/// Attributes never written as blocks.
+const ATTRIBUTES: &[&str] = &["default", "value"];
+
+/// Render `document` using Terraform's native syntax (i.e. the content of a `.tf` file).
+///
+/// # Panics
+///
+/// Will panic if `document` cannot be converted to JSON.
+pub fn to_string(document: &Document) -> String {
+    let blocks = blocks(document);
+    let document = serde_json::to_value(document).expect("valid JSON document");
+    value_to_string(&document, &blocks)
+}
+
+/// Paths of the nested blocks of resources and data sources, keyed by their address (e.g.
+/// `data.kubernetes_namespace.default`).
+pub(crate) type Blocks = BTreeMap<String, Vec<String>>;
+
+/// Returns the recorded paths of nested blocks of all resources and data sources in `document`,
+/// which are not part of its JSON representation.
+pub(crate) fn blocks(document: &Document) -> Blocks {
+    let kinds = [("data", &document.data), ("resource", &document.resource)];
+    let mut blocks = Blocks::new();
+    for (kind, types) in kinds {
+        for (ty, objects) in types {
+            for (name, object) in objects {
+                if let Some(paths) = &object.meta.metadata.blocks {
+                    blocks.insert(format!("{kind}.{ty}.{name}"), paths.clone());
+                }
+            }
+        }
+    }
+    blocks
+}
+
+/// Render a Terraform JSON document using Terraform's native syntax. Missing top-level blocks
+/// are skipped, so `document` may contain a part of a configuration only. The paths of nested
+/// blocks are looked up in `blocks` (see [`blocks`]).
+pub(crate) fn value_to_string(document: &Value, blocks: &Blocks) -> String {
+    let mut writer = Writer::default();
+    if let Some(terraform) = document.get("terraform") {
+        let mut terraform = object(terraform).clone();
+        let backends = terraform.remove("backend");
+        writer.open(&["terraform"]);
+        writer.body(&terraform);
+        for (name, config) in backends.iter().flat_map(object) {
+            writer.separate();
+            writer.block(&["backend", name], object(config));
+        }
+        writer.close();
+    }
+    for (name, configs) in document.get("provider").iter().flat_map(|v| object(v)) {
+        for config in configs.as_array().into_iter().flatten() {
+            writer.top_level(&["provider", name], object(config));
+        }
+    }
+    for (name, config) in document.get("variable").iter().flat_map(|v| object(v)) {
+        let mut config = object(config).clone();
+        // Type constraints are expressions (e.g. `list(string)`).
+        if let Some(Value::String(ty)) = config.get_mut("type") {
+            *ty = format!("${{{ty}}}");
+        }
+        writer.top_level(&["variable", name], &config);
+    }
+    if let Some(locals) = document.get("locals").map(object) {
+        if !locals.is_empty() {
+            writer.separate();
+            writer.open(&["locals"]);
+            writer.attributes(&locals.iter().collect::<Vec<_>>());
+            writer.close();
+        }
+    }
+    for kind in ["data", "resource"] {
+        for (ty, objects) in document.get(kind).iter().flat_map(|v| object(v)) {
+            for (name, config) in object(objects) {
+                writer.blocks = blocks.get(&format!("{kind}.{ty}.{name}")).cloned();
+                writer.traversals = TRAVERSALS;
+                writer.top_level(&[kind, ty, name], object(config));
+                writer.blocks = None;
+                writer.traversals = &[];
+            }
+        }
+    }
+    for (name, config) in document.get("output").iter().flat_map(|v| object(v)) {
+        writer.traversals = OUTPUT_TRAVERSALS;
+        writer.top_level(&["output", name], object(config));
+        writer.traversals = &[];
+    }
+    writer.out
+}
+
+fn object(value: &Value) -> &Map<String, Value> {
+    value.as_object().expect("JSON object")
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    indent: usize,
+    /// Paths of the nested blocks of the current resource. Guessed using the structure of the
+    /// configuration if `None`.
+    blocks: Option<Vec<String>>,
+    /// Path of the current nested block.
+    path: Vec<String>,
+    /// Paths of the attributes of the current block written without quotes (e.g.
+    /// [`TRAVERSALS`]).
+    traversals: &'static [&'static str],
+}
+
+impl Writer {
+    fn line(&mut self, line: &str) {
+        writeln!(self.out, "{:1$}{line}", "", self.indent * 2).unwrap();
+    }
+
+    /// Add an empty line if this is not the first line of a body.
+    fn separate(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("{\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn open(&mut self, labels: &[&str]) {
+        let mut header = labels[0].to_string();
+        for label in &labels[1..] {
+            header += &format!(" \"{}\"", escape(label));
+        }
+        self.line(&format!("{header} {{"));
+        self.indent += 1;
+    }
+
+    fn close(&mut self) {
+        self.indent -= 1;
+        self.line("}");
+    }
+
+    fn top_level(&mut self, labels: &[&str], body: &Map<String, Value>) {
+        self.separate();
+        self.block(labels, body);
+    }
+
+    fn block(&mut self, labels: &[&str], body: &Map<String, Value>) {
+        self.open(labels);
+        self.body(body);
+        if self.out.ends_with("{\n") {
+            // Write empty blocks on a single line.
+            self.out.truncate(self.out.len() - 2);
+            self.out.push_str("{}\n");
+            self.indent -= 1;
+        } else {
+            self.close();
+        }
+    }
+
+    fn body(&mut self, body: &Map<String, Value>) {
+        let fields: Vec<_> = body
+            .iter()
+            .filter(|(key, value)| *key != "//" && !value.is_null())
+            .collect();
+        let leading = fields
+            .iter()
+            .copied()
+            .filter(|(key, _)| LEADING.contains(&key.as_str()));
+        let trailing = fields
+            .iter()
+            .copied()
+            .filter(|(key, _)| TRAILING.contains(&key.as_str()));
+        let other = fields.iter().copied().filter(|(key, _)| {
+            !LEADING.contains(&key.as_str()) && !TRAILING.contains(&key.as_str())
+        });
+        let (blocks, attributes): (Vec<_>, Vec<_>) =
+            other.partition(|(key, value)| self.is_block(key, value));
+        let attributes: Vec<_> = leading.chain(attributes).collect();
+        let (trailing_blocks, trailing_attributes): (Vec<_>, Vec<_>) =
+            trailing.partition(|(key, value)| self.is_block(key, value));
+
+        self.attributes(&attributes);
+        for (key, value) in blocks {
+            self.nested_block(key, value);
+        }
+        if !trailing_attributes.is_empty() {
+            self.separate();
+            self.attributes(&trailing_attributes);
+        }
+        for (key, value) in trailing_blocks {
+            self.nested_block(key, value);
+        }
+    }
+
+    /// Write `attributes` with their equal signs aligned (see [`align`]).
+    fn attributes(&mut self, attributes: &[(&String, &Value)]) {
+        let rendered: Vec<_> = attributes
+            .iter()
+            .map(|(key, value)| {
+                let path: Vec<_> = self
+                    .path
+                    .iter()
+                    .map(String::as_str)
+                    .chain([key.as_str()])
+                    .collect();
+                let is_traversal = self.traversals.contains(&path.join(".").as_str());
+                let value = match value {
+                    Value::String(text) if is_traversal => text.clone(),
+                    Value::Array(items) if is_traversal => {
+                        let items: Vec<_> = items.iter().map(traversal).collect();
+                        format!("[{}]", items.join(", "))
+                    }
+                    value => expression(value, self.indent),
+                };
+                (key.to_string(), value)
+            })
+            .collect();
+        for line in align(&rendered) {
+            self.line(&line);
+        }
+    }
+
+    /// Returns `true` if `value` is written as nested block(s) named `key`.
+    fn is_block(&self, key: &str, value: &Value) -> bool {
+        let blocks = match &self.blocks {
+            Some(blocks) if !BLOCKS.contains(&key) => blocks,
+            _ => return is_block(key, value),
+        };
+        let path: Vec<_> = self.path.iter().map(String::as_str).chain([key]).collect();
+        let is_object = match value {
+            Value::Object(_) => true,
+            Value::Array(items) => items.iter().all(Value::is_object),
+            _ => false,
+        };
+        is_object && blocks.contains(&path.join("."))
+    }
+
+    fn nested_block(&mut self, key: &str, value: &Value) {
+        // The content of meta-arguments (e.g. `lifecycle`) is not part of the block paths.
+        let meta = BLOCKS.contains(&key);
+        let blocks = if meta { self.blocks.take() } else { None };
+        self.path.push(key.to_string());
+        self.write_nested_block(key, value);
+        self.path.pop();
+        if meta {
+            self.blocks = blocks;
+        }
+    }
+
+    fn write_nested_block(&mut self, key: &str, value: &Value) {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.separate();
+                    self.block(&[key], object(item));
+                }
+            }
+            value => {
+                self.separate();
+                self.block(&[key], object(value));
+            }
+        }
+    }
+}
+
+/// Returns the lines `key = value` of `attributes` with their equal signs aligned. Similar to
+/// `terraform fmt`, a multi-line value will end the aligned group.
+fn align(attributes: &[(String, String)]) -> Vec<String> {
+    let mut width = 0;
+    let mut lines = Vec::new();
+    for (i, (key, value)) in attributes.iter().enumerate() {
+        if i == 0 || attributes[i - 1].1.contains('\n') {
+            width = attributes[i..]
+                .iter()
+                .take_while(|(_, value)| !value.contains('\n'))
+                .map(|(key, _)| key.len())
+                .max()
+                .unwrap_or(key.len());
+        }
+        let width = if value.contains('\n') {
+            key.len()
+        } else {
+            width
+        };
+        lines.push(format!("{key:width$} = {value}"));
+    }
+    lines
+}
+
+/// Returns `true` if `value` is written as nested block(s) named `key`. Used if the paths of
+/// nested blocks are unknown.
+fn is_block(key: &str, value: &Value) -> bool {
+    match value {
+        Value::Object(_) => BLOCKS.contains(&key),
+        Value::Array(items) => {
+            !ATTRIBUTES.contains(&key) && !items.is_empty() && items.iter().all(Value::is_object)
+        }
+        _ => false,
+    }
+}
+
+fn traversal(value: &Value) -> String {
+    match value {
+        Value::String(text) => unwrap_interpolation(text).unwrap_or(text).to_string(),
+        value => expression(value, 0),
+    }
+}
+
+/// Render `value` as an expression. Strings consisting of a single interpolation (e.g.
+/// `${var.name}`) will be written as the interpolated expression itself.
+fn expression(value: &Value, indent: usize) -> String {
+    let pad = |indent: usize| " ".repeat(indent * 2);
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        Value::String(text) => match unwrap_interpolation(text) {
+            Some(expression) => expression.to_string(),
+            None => string(text),
+        },
+        Value::Array(items) if items.iter().any(|v| v.is_array() || v.is_object()) => {
+            let mut out = "[\n".to_string();
+            for item in items {
+                out += &format!("{}{},\n", pad(indent + 1), expression(item, indent + 1));
+            }
+            out + &pad(indent) + "]"
+        }
+        Value::Array(items) => {
+            let items: Vec<_> = items.iter().map(|v| expression(v, indent)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(fields) if fields.is_empty() => "{}".to_string(),
+        Value::Object(fields) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|(key, value)| {
+                    let key = if is_identifier(key) {
+                        key.clone()
+                    } else {
+                        quote(key)
+                    };
+                    (key, expression(value, indent + 1))
+                })
+                .collect();
+            let mut out = "{\n".to_string();
+            for line in align(&fields) {
+                out += &format!("{}{line}\n", pad(indent + 1));
+            }
+            out + &pad(indent) + "}"
+        }
+    }
+}
+
+/// Render `text` as a quoted template or as a heredoc if it spans multiple lines.
+fn string(text: &str) -> String {
+    let lines = text.lines().count();
+    if text.ends_with('\n') && lines > 1 {
+        let mut delimiter = "EOT".to_string();
+        while text.lines().any(|line| line.trim() == delimiter) {
+            delimiter += "_";
+        }
+        return format!("<<{delimiter}\n{text}{delimiter}");
+    }
+    let mut out = String::from('"');
+    for (is_template, segment) in segments(text) {
+        if is_template {
+            out += segment;
+        } else {
+            out += &escape(segment);
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Quote a literal string (e.g. an object key).
+fn quote(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        escape(&text.replace("${", "$${").replace("%{", "%%{"))
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// Returns the expression of `text` if it consists of a single interpolation.
+fn unwrap_interpolation(text: &str) -> Option<&str> {
+    match segments(text).as_slice() {
+        [(true, segment)] if segment.starts_with("${") => {
+            Some(segment[2..segment.len() - 1].trim())
+        }
+        _ => None,
+    }
+}
+
+/// Split a template into literal and template sequences (i.e. `${...}` and `%{...}`). Template
+/// sequences are marked with `true`. Escaped sequences (i.e. `$${` and `%%{`) are part of the
+/// literal segments.
+fn segments(text: &str) -> Vec<(bool, &str)> {
+    let bytes = text.as_bytes();
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if rest.starts_with(b"$${") || rest.starts_with(b"%%{") {
+            i += 3;
+            continue;
+        }
+        if !rest.starts_with(b"${") && !rest.starts_with(b"%{") {
+            i += 1;
+            continue;
+        }
+        let Some(end) = template_end(&text[i + 2..]) else {
+            break;
+        };
+        if start < i {
+            segments.push((false, &text[start..i]));
+        }
+        let end = i + 2 + end + 1;
+        segments.push((true, &text[i..end]));
+        start = end;
+        i = end;
+    }
+    if start < text.len() {
+        segments.push((false, &text[start..]));
+    }
+    segments
+}
+
+/// Returns the index of the brace closing a template sequence. Braces inside of string
+/// literals are ignored.
+fn template_end(text: &str) -> Option<usize> {
+    let mut depth = 1;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{expression, value_to_string, Writer};
+
+    fn body(value: serde_json::Value) -> String {
+        let mut writer = Writer {
+            traversals: super::TRAVERSALS,
+            ..Default::default()
+        };
+        writer.block(
+            &["resource", "kubernetes_pod", "nginx"],
+            super::object(&value),
+        );
+        writer.out
+    }
+
+    #[test]
+    fn resource_block() {
+        let hcl = body(json!({
+            "//": { "metadata": { "path": "stack/nginx" } },
+            "id": null,
+            "labels": { "app": "nginx", "app.kubernetes.io/name": "nginx" },
+            "metadata": [{ "name": "nginx", "namespace": null, "provider": "west" }],
+            "provider": "kubernetes.west",
+            "depends_on": ["kubernetes_namespace.default"],
+            "lifecycle": { "ignore_changes": "all" },
+            "spec": [{ "container": [{ "image": "nginx" }, { "image": "redis" }] }]
+        }));
+        let expected = r#"resource "kubernetes_pod" "nginx" {
+  provider = kubernetes.west
+  labels = {
+    app                      = "nginx"
+    "app.kubernetes.io/name" = "nginx"
+  }
+
+  metadata {
+    provider = "west"
+    name     = "nginx"
+  }
+
+  spec {
+    container {
+      image = "nginx"
+    }
+
+    container {
+      image = "redis"
+    }
+  }
+
+  depends_on = [kubernetes_namespace.default]
+
+  lifecycle {
+    ignore_changes = all
+  }
+}
+"#;
+        assert_eq!(hcl, expected);
+    }
+
+    #[test]
+    fn recorded_blocks() {
+        let document = json!({
+            "resource": {
+                "kubernetes_service": {
+                    "nginx": {
+                        "//": {
+                            "metadata": {
+                                "path": "stack/nginx",
+                                "uniqueId": "nginx"
+                            }
+                        },
+                        "ports": [{ "name": "http", "port": 80 }],
+                        "spec": { "selector": [{ "app": "nginx" }], "type": "ClusterIP" },
+                        "timeouts": { "create": "5m" },
+                        "lifecycle": {
+                            "precondition": [{ "condition": "${true}", "error_message": "never" }]
+                        }
+                    }
+                }
+            }
+        });
+        let expected = r#"resource "kubernetes_service" "nginx" {
+  ports = [
+    {
+      name = "http"
+      port = 80
+    },
+  ]
+
+  spec {
+    type = "ClusterIP"
+
+    selector {
+      app = "nginx"
+    }
+  }
+
+  timeouts {
+    create = "5m"
+  }
+
+  lifecycle {
+    precondition {
+      condition     = true
+      error_message = "never"
+    }
+  }
+}
+"#;
+        let blocks = ["spec", "spec.selector", "timeouts"].map(str::to_string);
+        let blocks = [(
+            "resource.kubernetes_service.nginx".to_string(),
+            blocks.to_vec(),
+        )];
+        assert_eq!(value_to_string(&document, &blocks.into()), expected);
+    }
+
+    #[test]
+    fn aligned_attributes() {
+        let hcl = body(json!({ "name": "nginx", "replicas": 3, "enabled": true }));
+        let expected = r#"resource "kubernetes_pod" "nginx" {
+  enabled  = true
+  name     = "nginx"
+  replicas = 3
+}
+"#;
+        assert_eq!(hcl, expected);
+        assert_eq!(
+            body(json!({ "id": null })),
+            "resource \"kubernetes_pod\" \"nginx\" {}\n"
+        );
+    }
+
+    #[test]
+    fn interpolation() {
+        let render = |value: serde_json::Value| expression(&value, 0);
+        assert_eq!(render(json!("${var.name}")), "var.name");
+        assert_eq!(render(json!("${var.name}-copy")), "\"${var.name}-copy\"");
+        assert_eq!(
+            render(json!("${format(\"%s}\", var.name)}")),
+            "format(\"%s}\", var.name)"
+        );
+        assert_eq!(
+            render(json!("$${literal} \"quoted\"")),
+            "\"$${literal} \\\"quoted\\\"\""
+        );
+        assert_eq!(render(json!("a\nb")), "\"a\\nb\"");
+        assert_eq!(render(json!("a\nb\n")), "<<EOT\na\nb\nEOT");
+        assert_eq!(render(json!("EOT\nb\n")), "<<EOT_\nEOT\nb\nEOT_");
+    }
+}
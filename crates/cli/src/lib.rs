@@ -1,44 +1,243 @@
-use serde_json::Value;
-use std::process::Command;
-use tf_bindgen_core::Stack;
+pub mod hcl;
 
-const PATH: &str = "target/stacks/";
+use serde_json::{json, Map, Value};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use tf_bindgen_core::{App, Stack};
+use tf_bindgen_schema::Document;
 
 /// Used to store the result of the terraform command.
 pub struct Terraform;
 
+/// Syntax used to write the synthesized configuration. Selected using [`SynthOptions::format`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Terraform's JSON syntax, written to `cdk.tf.json`.
+    #[default]
+    Json,
+    /// Terraform's native syntax, written to `main.tf`. See [`hcl::to_string`].
+    Hcl,
+}
+
+/// Used to configure where and how stacks are synthesized. By default, every stack is written
+/// to `target/stacks/<stack name>/cdk.tf.json`.
+///
+/// # Usage
+///
+/// ```rust,no_run
+/// use tf_bindgen_cli::{Format, SynthOptions, Terraform};
+/// use tf_bindgen_core::Stack;
+///
+/// let stack = Stack::new("nginx");
+/// let mut options = SynthOptions::default();
+/// options.out_dir("deploy").format(Format::Hcl).split_by_type();
+/// Terraform::synth(&stack, &options).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct SynthOptions {
+    out_dir: PathBuf,
+    file_name: Option<String>,
+    format: Format,
+    pretty: bool,
+    split_by_type: bool,
+}
+
+impl Format {
+    /// Returns the name of the file the configuration is written to.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Format::Json => "cdk.tf.json",
+            Format::Hcl => "main.tf",
+        }
+    }
+
+    /// Returns the file extension expected by Terraform.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => ".tf.json",
+            Format::Hcl => ".tf",
+        }
+    }
+}
+
+impl Default for SynthOptions {
+    fn default() -> Self {
+        Self {
+            out_dir: PathBuf::from("target/stacks"),
+            file_name: None,
+            format: Format::Json,
+            pretty: true,
+            split_by_type: false,
+        }
+    }
+}
+
+impl SynthOptions {
+    /// Set the directory stacks are written to (default: `target/stacks`). Every stack is
+    /// written to a subdirectory named after the stack.
+    pub fn out_dir(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.out_dir = path.into();
+        self
+    }
+
+    /// Set the name of the written configuration file (default: see [`Format::file_name`]).
+    /// The name must be a plain file name ending with the extension of the used format (see
+    /// [`Format::extension`]). Otherwise, synthesizing a stack will fail.
+    pub fn file_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.file_name = Some(name.into());
+        self
+    }
+
+    /// Set the syntax used to write the configuration (default: [`Format::Json`]).
+    pub fn format(&mut self, format: Format) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// Write JSON configurations without indentation.
+    pub fn compact(&mut self) -> &mut Self {
+        self.pretty = false;
+        self
+    }
+
+    /// Write the resources and data sources of every type to a separate file (e.g.
+    /// `resource.kubernetes_pod.tf.json` or `data.kubernetes_namespace.tf.json`). All other
+    /// blocks are written to the configuration file.
+    pub fn split_by_type(&mut self) -> &mut Self {
+        self.split_by_type = true;
+        self
+    }
+
+    /// Returns the directory `stack` is written to.
+    pub fn stack_dir(&self, stack: &Stack) -> PathBuf {
+        self.out_dir.join(stack.name())
+    }
+
+    fn main_file(&self) -> &str {
+        self.file_name
+            .as_deref()
+            .unwrap_or_else(|| self.format.file_name())
+    }
+
+    fn render(&self, mut document: Map<String, Value>, blocks: &hcl::Blocks) -> String {
+        let fields = vec![
+            "data", "resource", "provider", "output", "variable", "locals",
+        ];
+        for field in fields {
+            if let Some(Value::Object(fields)) = document.get(field) {
+                if fields.is_empty() {
+                    document.remove(field);
+                }
+            }
+        }
+        let document = Value::Object(document);
+        match self.format {
+            Format::Hcl => hcl::value_to_string(&document, blocks),
+            Format::Json if self.pretty => serde_json::to_string_pretty(&document).unwrap(),
+            Format::Json => serde_json::to_string(&document).unwrap(),
+        }
+    }
+}
+
 impl Terraform {
-    /// Generates the Terraform JSON configuration and writes to a file at
-    /// `target/stacks/{stack_name}/cdk.tf.json`.
+    /// Generates the Terraform configuration of `stack` and writes it to the stack directory
+    /// (see [`SynthOptions::stack_dir`]). Configuration files previously synthesized using
+    /// other options (e.g. another format) will be removed, so Terraform will not load both.
+    /// The written files are recorded in `.tf-bindgen` inside of the stack directory.
     ///
     /// # Errors
     ///
-    /// Will return `Err` if failed to write JSON document or to create stack directory.
+    /// Will return `Err` if failed to write document or to create stack directory. Will also
+    /// return `Err` if the configured file name is invalid (see [`SynthOptions::file_name`])
+    /// or `stack` references other stacks (see [`Terraform::synth_app`]).
     ///
     /// # Panics
     ///
     /// Will panic if failed to generate JSON document.
-    pub fn synth(stack: &Stack) -> std::io::Result<()> {
+    pub fn synth(stack: &Stack, options: &SynthOptions) -> std::io::Result<()> {
         let document = stack.to_document();
-        let document = serde_json::to_value(&document).expect("valid JSON document");
-        let document = match document {
-            Value::Object(mut mapping) => {
-                let fields = vec!["data", "resource", "provider"];
-                for field in fields {
-                    if let Some(Value::Object(fields)) = mapping.get(field) {
-                        if fields.is_empty() {
-                            mapping.remove(field);
-                        }
-                    }
+        let referenced = stack.referenced_stacks();
+        if !referenced.is_empty() {
+            let referenced: Vec<_> = referenced.into_iter().collect();
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "stack '{}' references stacks '{}' and must be synthesized as part of an app",
+                    stack.name(),
+                    referenced.join("', '")
+                ),
+            ));
+        }
+        Self::write(document, &options.stack_dir(stack), options)
+    }
+
+    /// Generates the Terraform configuration of all stacks of `app` (see
+    /// [`App::to_documents`]) and writes each of them to its stack directory (see
+    /// [`Terraform::synth`]).
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if failed to write document or to create stack directory. Will also
+    /// return `Err` if the stacks of `app` cannot be synthesized (see [`App::to_documents`]).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if failed to generate JSON documents.
+    pub fn synth_app(app: &App, options: &SynthOptions) -> std::io::Result<()> {
+        Self::write_app(app, options)?;
+        Ok(())
+    }
+
+    /// Write the documents of `app`. Returns the stack directories in order of deployment.
+    fn write_app(app: &App, options: &SynthOptions) -> std::io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        let documents = app
+            .to_documents()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        for document in documents {
+            let path = options.out_dir.join(&document.meta.metadata.stack_name);
+            Self::write(document, &path, options)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    fn write(document: Document, path: &Path, options: &SynthOptions) -> std::io::Result<()> {
+        let main_file = options.main_file();
+        let extension = options.format.extension();
+        if !is_file_name(main_file) || !main_file.ends_with(extension) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("configuration file name `{main_file}` must end with `{extension}`"),
+            ));
+        }
+        std::fs::create_dir_all(path)?;
+        let blocks = hcl::blocks(&document);
+        let Value::Object(mut document) = serde_json::to_value(document)? else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "configuration must be a JSON object",
+            ));
+        };
+        let mut files = Vec::new();
+        if options.split_by_type {
+            for kind in ["data", "resource"] {
+                let Some(Value::Object(types)) = document.remove(kind) else {
+                    continue;
+                };
+                for (ty, objects) in types {
+                    let file = format!("{kind}.{ty}{}", options.format.extension());
+                    let mut document = Map::new();
+                    document.insert(kind.to_string(), json!({ ty: objects }));
+                    files.push((file, document));
                 }
-                Value::Object(mapping)
             }
-            _ => unimplemented!(),
-        };
-        let document = serde_json::to_string_pretty(&document).unwrap();
-        let path = format!("{PATH}/{}", stack.name());
-        std::fs::create_dir_all(&path)?;
-        std::fs::write(format!("{path}/cdk.tf.json"), document)?;
+        }
+        files.insert(0, (main_file.to_string(), document));
+        remove_stale_files(path, &files)?;
+        for (file, document) in files {
+            std::fs::write(path.join(file), options.render(document, &blocks))?;
+        }
         Ok(())
     }
 
@@ -52,13 +251,8 @@ impl Terraform {
     /// # Panics
     ///
     /// Will panic if failed to generate document (see [`Terraform::synth`]).
-    pub fn init(stack: &Stack) -> std::io::Result<Command> {
-        Self::synth(stack)?;
-        let mut command = Command::new("terraform");
-        let path = format!("{PATH}/{}", stack.name());
-        command.arg(format!("-chdir={}", path));
-        command.arg("init");
-        Ok(command)
+    pub fn init(stack: &Stack, options: &SynthOptions) -> std::io::Result<Command> {
+        Self::command(stack, options, "init")
     }
 
     /// Will synthesize (see [`Terraform::synth`]). Returns a prepared Terraform command to run
@@ -71,13 +265,8 @@ impl Terraform {
     /// # Panics
     ///
     /// Will panic if failed to generate document (see [`Terraform::synth`]).
-    pub fn validate(stack: &Stack) -> std::io::Result<Command> {
-        Self::synth(stack)?;
-        let mut command = Command::new("terraform");
-        let path = format!("{PATH}/{}", stack.name());
-        command.arg(format!("-chdir={}", path));
-        command.arg("validate");
-        Ok(command)
+    pub fn validate(stack: &Stack, options: &SynthOptions) -> std::io::Result<Command> {
+        Self::command(stack, options, "validate")
     }
 
     /// Will synthesize (see [`Terraform::synth`]). Returns a prepared Terraform command to run
@@ -90,13 +279,8 @@ impl Terraform {
     /// # Panics
     ///
     /// Will panic if failed to generate document (see [`Terraform::synth`]).
-    pub fn apply(stack: &Stack) -> std::io::Result<Command> {
-        Self::synth(stack)?;
-        let mut command = Command::new("terraform");
-        let path = format!("{PATH}/{}", stack.name());
-        command.arg(format!("-chdir={}", path));
-        command.arg("apply");
-        Ok(command)
+    pub fn apply(stack: &Stack, options: &SynthOptions) -> std::io::Result<Command> {
+        Self::command(stack, options, "apply")
     }
 
     /// Will synthesize (see [`Terraform::synth`]). Returns a prepared Terraform command to run
@@ -109,13 +293,8 @@ impl Terraform {
     /// # Panics
     ///
     /// Will panic if failed to generate document (see [`Terraform::synth`]).
-    pub fn destroy(stack: &Stack) -> std::io::Result<Command> {
-        Self::synth(stack)?;
-        let mut command = Command::new("terraform");
-        let path = format!("{PATH}/{}", stack.name());
-        command.arg(format!("-chdir={}", path));
-        command.arg("destroy");
-        Ok(command)
+    pub fn destroy(stack: &Stack, options: &SynthOptions) -> std::io::Result<Command> {
+        Self::command(stack, options, "destroy")
     }
 
     /// Will synthesize (see [`Terraform::synth`]). Returns a prepared Terraform command to run
@@ -128,12 +307,303 @@ impl Terraform {
     /// # Panics
     ///
     /// Will panic if failed to generate document (see [`Terraform::synth`]).
-    pub fn plan(stack: &Stack) -> std::io::Result<Command> {
-        Self::synth(stack)?;
-        let mut command = Command::new("terraform");
-        let path = format!("{PATH}/{}", stack.name());
-        command.arg(format!("-chdir={}", path));
-        command.arg("plan");
-        Ok(command)
+    pub fn plan(stack: &Stack, options: &SynthOptions) -> std::io::Result<Command> {
+        Self::command(stack, options, "plan")
+    }
+
+    /// Will synthesize `app` (see [`Terraform::synth_app`]). Returns prepared Terraform
+    /// commands to run initialization of every stack.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if failed to synthesize app (see [`Terraform::synth_app`]).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if failed to generate documents (see [`Terraform::synth_app`]).
+    pub fn init_app(app: &App, options: &SynthOptions) -> std::io::Result<Vec<Command>> {
+        Self::app_commands(app, options, "init")
+    }
+
+    /// Will synthesize `app` (see [`Terraform::synth_app`]). Returns prepared Terraform
+    /// commands to run validate for every stack.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if failed to synthesize app (see [`Terraform::synth_app`]).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if failed to generate documents (see [`Terraform::synth_app`]).
+    pub fn validate_app(app: &App, options: &SynthOptions) -> std::io::Result<Vec<Command>> {
+        Self::app_commands(app, options, "validate")
+    }
+
+    /// Will synthesize `app` (see [`Terraform::synth_app`]). Returns prepared Terraform
+    /// commands to deploy every stack. Commands are ordered such that referenced stacks are
+    /// deployed first.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if failed to synthesize app (see [`Terraform::synth_app`]).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if failed to generate documents (see [`Terraform::synth_app`]).
+    pub fn apply_app(app: &App, options: &SynthOptions) -> std::io::Result<Vec<Command>> {
+        Self::app_commands(app, options, "apply")
+    }
+
+    /// Will synthesize `app` (see [`Terraform::synth_app`]). Returns prepared Terraform
+    /// commands to destroy every stack. Commands are ordered such that referenced stacks are
+    /// destroyed last.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if failed to synthesize app (see [`Terraform::synth_app`]).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if failed to generate documents (see [`Terraform::synth_app`]).
+    pub fn destroy_app(app: &App, options: &SynthOptions) -> std::io::Result<Vec<Command>> {
+        let mut commands = Self::app_commands(app, options, "destroy")?;
+        commands.reverse();
+        Ok(commands)
+    }
+
+    /// Will synthesize `app` (see [`Terraform::synth_app`]). Returns prepared Terraform
+    /// commands to run plan for every stack. Note that outputs of stacks not yet deployed
+    /// cannot be read by the stacks referencing them.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if failed to synthesize app (see [`Terraform::synth_app`]).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if failed to generate documents (see [`Terraform::synth_app`]).
+    pub fn plan_app(app: &App, options: &SynthOptions) -> std::io::Result<Vec<Command>> {
+        Self::app_commands(app, options, "plan")
+    }
+
+    /// Synthesize `app` and prepare the Terraform command `subcommand` for every stack in
+    /// order of deployment.
+    fn app_commands(
+        app: &App,
+        options: &SynthOptions,
+        subcommand: &str,
+    ) -> std::io::Result<Vec<Command>> {
+        let commands = Self::write_app(app, options)?
+            .into_iter()
+            .map(|path| terraform(&path, subcommand))
+            .collect();
+        Ok(commands)
+    }
+
+    /// Synthesize `stack` and prepare the Terraform command `subcommand` running inside of
+    /// the stack directory.
+    fn command(
+        stack: &Stack,
+        options: &SynthOptions,
+        subcommand: &str,
+    ) -> std::io::Result<Command> {
+        Self::synth(stack, options)?;
+        Ok(terraform(&options.stack_dir(stack), subcommand))
+    }
+}
+
+/// Prepare the Terraform command `subcommand` running inside of `path`.
+fn terraform(path: &Path, subcommand: &str) -> Command {
+    let mut command = Command::new("terraform");
+    command.arg(format!("-chdir={}", path.display()));
+    command.arg(subcommand);
+    command
+}
+
+/// Name of the file listing the configuration files written by the last synthesis.
+const MANIFEST: &str = ".tf-bindgen";
+
+/// Remove configuration files written by a previous synthesis, which are not part of `files`.
+/// Only files listed in the manifest of the previous synthesis are removed. Entries of the
+/// manifest, which are not a plain file name (e.g. `../main.tf`), are ignored. Afterwards,
+/// `files` are recorded in the manifest.
+fn remove_stale_files(path: &Path, files: &[(String, Map<String, Value>)]) -> std::io::Result<()> {
+    let manifest = path.join(MANIFEST);
+    let previous = match std::fs::read_to_string(&manifest) {
+        Ok(previous) => previous,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let stale = previous
+        .lines()
+        .filter(|name| is_file_name(name))
+        .filter(|name| !files.iter().any(|(file, _)| file == name));
+    for name in stale {
+        let file = path.join(name);
+        if file.exists() {
+            std::fs::remove_file(file)?;
+        }
+    }
+    let names: String = files.iter().map(|(file, _)| format!("{file}\n")).collect();
+    std::fs::write(manifest, names)
+}
+
+/// Returns `true` if `name` is a file name without any directory components.
+fn is_file_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use tempfile::TempDir;
+    use tf_bindgen_core::{App, L1Construct, Path, Scope, Stack};
+    use tf_bindgen_schema::document::{Resource, ResourceMeta, ResourceMetadata};
+
+    use super::{Format, SynthOptions, Terraform, MANIFEST};
+
+    struct TestResource {
+        stack: Stack,
+    }
+
+    impl Scope for TestResource {
+        fn stack(&self) -> Stack {
+            self.stack.clone()
+        }
+
+        fn path(&self) -> Path {
+            let mut path = self.stack.path();
+            path.push("nginx");
+            path
+        }
+    }
+
+    impl L1Construct for TestResource {
+        fn to_schema(&self) -> (String, Resource) {
+            let metadata = ResourceMetadata {
+                path: self.path().to_string(),
+                unique_id: "nginx".to_string(),
+                blocks: Some(vec!["spec".to_string()]),
+            };
+            let spec = serde_json::json!({ "replicas": 1 });
+            let resource = Resource {
+                meta: ResourceMeta { metadata },
+                config: [("spec".to_string(), spec)].into(),
+            };
+            ("kubernetes_pod".to_string(), resource)
+        }
+    }
+
+    #[test]
+    fn split_by_type() {
+        let stack = Stack::new("split");
+        let resource = Rc::new(TestResource {
+            stack: (*stack).clone(),
+        });
+        stack.add_resource(resource);
+        let dir = TempDir::new().unwrap();
+        let out_dir = dir.path();
+        let mut options = SynthOptions::default();
+        options
+            .out_dir(out_dir)
+            .file_name("main.tf.json")
+            .compact()
+            .split_by_type();
+        Terraform::synth(&stack, &options).unwrap();
+        let path = out_dir.join("split");
+        let main = std::fs::read_to_string(path.join("main.tf.json")).unwrap();
+        assert!(!main.contains('\n') && !main.contains("\"resource\""));
+        let resources = std::fs::read_to_string(path.join("resource.kubernetes_pod.tf.json"));
+        assert!(resources
+            .unwrap()
+            .starts_with(r#"{"resource":{"kubernetes_pod":{"#));
+
+        Terraform::synth(&stack, SynthOptions::default().out_dir(out_dir)).unwrap();
+        assert!(path.join("cdk.tf.json").exists());
+        assert!(!path.join("main.tf.json").exists());
+        assert!(!path.join("resource.kubernetes_pod.tf.json").exists());
+    }
+
+    #[test]
+    fn recorded_blocks() {
+        let stack = Stack::new("nested");
+        let resource = Rc::new(TestResource {
+            stack: (*stack).clone(),
+        });
+        stack.add_resource(resource);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested");
+        Terraform::synth(&stack, SynthOptions::default().out_dir(dir.path())).unwrap();
+        let json = std::fs::read_to_string(path.join("cdk.tf.json")).unwrap();
+        assert!(!json.contains("\"blocks\""));
+
+        let mut options = SynthOptions::default();
+        options.out_dir(dir.path()).format(Format::Hcl);
+        Terraform::synth(&stack, &options).unwrap();
+        let hcl = std::fs::read_to_string(path.join("main.tf")).unwrap();
+        assert!(hcl.contains("  spec {\n    replicas = 1\n  }\n"));
+    }
+
+    #[test]
+    fn invalid_file_name() {
+        let stack = Stack::new("invalid");
+        let dir = TempDir::new().unwrap();
+        let mut options = SynthOptions::default();
+        options.out_dir(dir.path()).file_name("main.tf");
+        let err = Terraform::synth(&stack, &options).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        options.format(Format::Hcl).file_name("../main.tf");
+        let err = Terraform::synth(&stack, &options).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!dir.path().join("invalid").exists());
+
+        options.file_name("main.tf");
+        Terraform::synth(&stack, &options).unwrap();
+        assert!(dir.path().join("invalid/main.tf").exists());
+    }
+
+    #[test]
+    fn keep_files_outside_of_stack() {
+        let stack = Stack::new("stack");
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("stack");
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(dir.path().join("other.tf"), "").unwrap();
+        std::fs::write(path.join("old.tf"), "").unwrap();
+        let manifest = format!("old.tf\n../other.tf\n{}/other.tf\n", dir.path().display());
+        std::fs::write(path.join(MANIFEST), manifest).unwrap();
+        Terraform::synth(&stack, SynthOptions::default().out_dir(dir.path())).unwrap();
+        assert!(!path.join("old.tf").exists());
+        assert!(dir.path().join("other.tf").exists());
+    }
+
+    #[test]
+    fn keep_unmanaged_files() {
+        let stack = Stack::new("unmanaged");
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("unmanaged");
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("main.tf"), "# hand-written").unwrap();
+        Terraform::synth(&stack, SynthOptions::default().out_dir(dir.path())).unwrap();
+        let main = std::fs::read_to_string(path.join("main.tf")).unwrap();
+        assert_eq!(main, "# hand-written");
+        assert!(path.join("cdk.tf.json").exists());
+    }
+
+    #[test]
+    fn reference_without_app() {
+        let app = App::new();
+        let network = app.stack("network");
+        let stack = Stack::new("service");
+        stack.local::<String>("network", &network.local::<String>("name", "network"));
+        let dir = TempDir::new().unwrap();
+        let err = Terraform::synth(&stack, SynthOptions::default().out_dir(dir.path()));
+        assert_eq!(err.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!dir.path().join("service").exists());
     }
 }
@@ -1,3 +1,6 @@
+// `#[darling(default)]` expands to code triggering this lint.
+#![allow(clippy::manual_unwrap_or_default)]
+
 use darling::ast::Data;
 use darling::{FromDeriveInput, FromField, FromMeta, ToTokens};
 use syn::{AngleBracketedGenericArguments, GenericArgument, PathArguments};
@@ -110,7 +113,9 @@ impl ToTokens for Construct {
                         SetterMode::Into => quote::quote!(value.into()),
                         SetterMode::IntoValue => quote::quote!(value.into_value()),
                         SetterMode::IntoValueList => quote::quote!(value.into_value_list()),
-                        SetterMode::IntoValueSet => quote::quote!(value.into_value_set()),
+                        SetterMode::IntoValueSet => {
+                            quote::quote!(value.into_value_set().into_iter().collect())
+                        }
                         SetterMode::IntoValueMap => quote::quote!(value.into_value_map()),
                         SetterMode::Default => quote::quote!(value),
                     };
@@ -214,6 +219,9 @@ pub fn unwrap_type(ty: &syn::Type, setter: &SetterMode) -> syn::Type {
                 let generic = get_nth_generic_argument(&path.path, 0);
                 unwrap_type(&generic, &SetterMode::IntoValue)
             }
+            syn::Type::Path(path) if is_ident(&path.path, "ValueSet") => {
+                get_nth_generic_argument(&path.path, 0)
+            }
             _ => unimplemented!("Cannot use IntoValue for non Value types."),
         },
         SetterMode::IntoValueMap => match ty {
@@ -8,6 +8,7 @@ mod resource;
 
 /// Used to generate a builder chain for a specified Terraform resource or data source. To
 /// describe the resource this macro will use a simplified version of Terraform's HCL syntax.
+/// Data sources can be created by using `data` instead of `resource`.
 ///
 /// # Usage
 ///
@@ -1,16 +1,18 @@
 use heck::ToUpperCamelCase;
-use quote::ToTokens;
 use quote::__private::TokenStream;
+use quote::ToTokens;
 use syn::parse::{Parse, ParseStream};
 use syn::Token;
 
 mod keyword {
     syn::custom_keyword!(resource);
+    syn::custom_keyword!(data);
     syn::custom_keyword!(scope);
 }
 
 pub struct Block {
     scope: syn::Expr,
+    data: bool,
     ty: syn::LitStr,
     name: syn::LitStr,
     body: Body,
@@ -28,11 +30,16 @@ pub enum Attribute {
 impl ToTokens for Block {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let name = self.ty.value().to_upper_camel_case();
+        let name = if self.data {
+            format!("Data{name}")
+        } else {
+            name
+        };
         let name = syn::Ident::new(&name, self.ty.span());
         let module = self.ty.value();
         let module = syn::Ident::new(&module, self.ty.span());
         let setter = self.body.to_setter_tokens();
-        let blocks = self.body.to_block_tokens(&name, &module);
+        let blocks = self.body.to_block_tokens(&name, &module, true);
         let scope = &self.scope;
         let name_str = &self.name;
         tokens.extend(quote::quote!(
@@ -62,16 +69,26 @@ impl Body {
             })
     }
 
+    /// Generate the nested blocks of this body. If `meta` is set, `lifecycle` blocks will be
+    /// generated as meta-arguments.
     fn to_block_tokens<'a>(
         &'a self,
         name: &'a syn::Ident,
         module: &'a syn::Ident,
+        meta: bool,
     ) -> impl Iterator<Item = TokenStream> + 'a {
         use Attribute::*;
         self.attributes
             .iter()
             .enumerate()
             .filter_map(move |(i, attr)| match attr {
+                Block { name: aname, body } if meta && aname == "lifecycle" => {
+                    let aname = syn::Ident::new(&format!("{aname}{i}"), aname.span());
+                    let lifecycle = body
+                        .to_lifecycle_tokens()
+                        .unwrap_or_else(|err| err.to_compile_error());
+                    Some((aname, lifecycle))
+                }
                 Block { name: aname, body } => {
                     let n = format!("{name}{}", aname.to_string().to_upper_camel_case());
                     let n = syn::Ident::new(&n, aname.span());
@@ -85,7 +102,7 @@ impl Body {
 
     fn to_tokens(&self, name: syn::Ident, module: &syn::Ident) -> TokenStream {
         let setter = self.to_setter_tokens();
-        let blocks = self.to_block_tokens(&name, module);
+        let blocks = self.to_block_tokens(&name, module, false);
         quote::quote!(
             {
                 #( #blocks )*
@@ -95,18 +112,95 @@ impl Body {
             }
         )
     }
+
+    /// Generate a lifecycle builder chain. `ignore_changes = all` and nested `precondition` or
+    /// `postcondition` blocks are translated into their corresponding setters.
+    fn to_lifecycle_tokens(&self) -> syn::Result<TokenStream> {
+        use Attribute::*;
+        let setter = self
+            .attributes
+            .iter()
+            .map(|attr| match attr {
+                Field { name, assign } if name == "ignore_changes" && is_ident(assign, "all") => {
+                    Ok(quote::quote!(.ignore_all_changes()))
+                }
+                Field { name, assign } => Ok(quote::quote!(.#name(#assign))),
+                Block { name, body } if name == "precondition" || name == "postcondition" => {
+                    let condition = body.to_condition_tokens(name)?;
+                    Ok(quote::quote!(.#name(#condition)))
+                }
+                Block { name, .. } => {
+                    let message = format!("unsupported block `{name}` inside of `lifecycle`");
+                    Err(syn::Error::new(name.span(), message))
+                }
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        Ok(quote::quote!(
+            ::tf_bindgen::Lifecycle::builder()
+                #( #setter )*
+                .build()
+        ))
+    }
+
+    /// Generate the arguments of the `precondition` or `postcondition` setter `block`.
+    fn to_condition_tokens(&self, block: &syn::Ident) -> syn::Result<TokenStream> {
+        let mut condition = None;
+        let mut error_message = None;
+        for attr in &self.attributes {
+            match attr {
+                Attribute::Field { name, assign } if name == "condition" => {
+                    condition = Some(assign)
+                }
+                Attribute::Field { name, assign } if name == "error_message" => {
+                    error_message = Some(assign)
+                }
+                Attribute::Field { name, .. } | Attribute::Block { name, .. } => {
+                    let message =
+                        format!("unexpected `{name}`, expected `condition` or `error_message`");
+                    return Err(syn::Error::new(name.span(), message));
+                }
+            }
+        }
+        let span = block.span();
+        let condition = condition.ok_or_else(|| syn::Error::new(span, "missing `condition`"))?;
+        let error_message =
+            error_message.ok_or_else(|| syn::Error::new(span, "missing `error_message`"))?;
+        Ok(quote::quote!(#condition, #error_message))
+    }
+}
+
+fn is_ident(expr: &syn::Expr, ident: &str) -> bool {
+    matches!(expr, syn::Expr::Path(path) if path.path.is_ident(ident))
 }
 
 impl Parse for Block {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let scope = input.parse()?;
         let _: Token![,] = input.parse()?;
-        let _: keyword::resource = input.parse()?;
+        let lookahead = input.lookahead1();
+        let data = if lookahead.peek(keyword::data) {
+            let _: keyword::data = input.parse()?;
+            true
+        } else if lookahead.peek(keyword::resource) {
+            let _: keyword::resource = input.parse()?;
+            false
+        } else {
+            return Err(lookahead.error());
+        };
         let ty = input.parse()?;
         let name = input.parse()?;
-        let body = input.parse()?;
+        let body: Body = input.parse()?;
+        for attr in &body.attributes {
+            match attr {
+                Attribute::Block { name, body } if name == "lifecycle" => {
+                    body.to_lifecycle_tokens()?;
+                }
+                _ => {}
+            }
+        }
         Ok(Self {
             scope,
+            data,
             ty,
             name,
             body,
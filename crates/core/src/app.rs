@@ -0,0 +1,488 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+use serde_json::Value;
+use tf_bindgen_schema::document::{Output, Resource, ResourceMeta, ResourceMetadata};
+use tf_bindgen_schema::Document;
+
+use crate::Stack;
+
+/// Used to manage multiple stacks deployed together. Stacks can reference values of other
+/// stacks of the same app (e.g. `&network.id`). When synthesized, such a reference is replaced
+/// by an output of the referenced stack, which is read using a `terraform_remote_state` data
+/// source.
+///
+/// Every reference records the stack declaring the referenced object, so multiple stacks may
+/// use the same names (e.g. `var.region`). This includes references used inside of
+/// expressions (e.g. function calls) or templates.
+///
+/// # Usage
+///
+/// ```rust
+/// use tf_bindgen_core::App;
+///
+/// let app = App::new();
+/// let network = app.stack("network");
+/// let service = app.stack("service");
+/// let name = network.local::<String>("name", "default");
+/// service.local::<String>("network", &name);
+/// let documents = app.to_documents().unwrap();
+/// assert_eq!(documents[0].meta.metadata.stack_name, "network");
+/// ```
+#[derive(Default)]
+pub struct App {
+    stacks: RefCell<Vec<Rc<Stack>>>,
+}
+
+/// Error returned if the stacks of an app cannot be synthesized. See [`App::to_documents`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AppError {
+    /// The stacks reference each other (in order of reference, e.g. `["a", "b"]` if `a`
+    /// references `b` and `b` references `a`).
+    Cycle(Vec<String>),
+    /// The stack `stack` references the stack `referenced`, which is not part of the app.
+    UnknownStack { stack: String, referenced: String },
+    /// The names of the stacks `stacks` are mapped to the same Terraform name `name`.
+    StackName { name: String, stacks: [String; 2] },
+    /// The referenced objects `traversals` of `stack` are exported using the same output
+    /// `output`.
+    Output {
+        stack: String,
+        output: String,
+        traversals: [String; 2],
+    },
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::Cycle(stacks) => {
+                let cycle: Vec<_> = stacks.iter().chain(stacks.first()).cloned().collect();
+                write!(f, "stacks reference each other: {}", cycle.join(" -> "))
+            }
+            AppError::UnknownStack { stack, referenced } => write!(
+                f,
+                "stack '{stack}' references stack '{referenced}', which is not part of this app"
+            ),
+            AppError::StackName {
+                name,
+                stacks: [a, b],
+            } => write!(
+                f,
+                "stacks '{a}' and '{b}' are both named '{name}' by Terraform"
+            ),
+            AppError::Output {
+                stack,
+                output,
+                traversals: [a, b],
+            } => write!(
+                f,
+                "references to '{a}' and '{b}' of stack '{stack}' use the same output '{output}'"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl App {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new stack named `name` managed by this app.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a stack named `name` already exists.
+    pub fn stack(&self, name: impl Into<String>) -> Rc<Stack> {
+        let stack = Stack::new(name);
+        let mut stacks = self.stacks.borrow_mut();
+        if stacks.iter().any(|other| other.name() == stack.name()) {
+            panic!("stack '{}' already exists", stack.name());
+        }
+        stacks.push(stack.clone());
+        stack
+    }
+
+    /// Returns the stacks of this app in order of creation.
+    pub fn stacks(&self) -> Vec<Rc<Stack>> {
+        self.stacks.borrow().clone()
+    }
+
+    /// Generate the Terraform JSON configuration of all stacks. References to other stacks are
+    /// resolved using outputs and `terraform_remote_state` data sources. The documents are
+    /// ordered such that every stack is placed after the stacks it references, i.e. in the
+    /// order the stacks must be applied.
+    ///
+    /// Remote states stored using the `local` backend are read using paths relative to the
+    /// referencing stack. Thus, all stacks must be written to the same directory (e.g. using
+    /// `tf_bindgen::cli::Terraform::synth_app`).
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if stacks reference each other, reference a stack not part of this
+    /// app or if the names of outputs or remote states collide (see [`AppError`]).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a document cannot be generated (see [`Stack::to_document`]).
+    pub fn to_documents(&self) -> Result<Vec<Document>, AppError> {
+        let stacks = self.stacks.borrow();
+        let mut documents: Vec<_> = stacks.iter().map(|stack| stack.to_document()).collect();
+        let mut names: BTreeMap<String, &str> = BTreeMap::new();
+        for stack in stacks.iter() {
+            if let Some(other) = names.insert(sanitize(stack.name()), stack.name()) {
+                return Err(AppError::StackName {
+                    name: sanitize(stack.name()),
+                    stacks: [other.to_string(), stack.name().to_string()],
+                });
+            }
+        }
+        let mut exports: Vec<BTreeMap<String, String>> = vec![BTreeMap::new(); stacks.len()];
+        let mut dependencies: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); stacks.len()];
+        for (index, stack) in stacks.iter().enumerate() {
+            for (name, traversal) in stack.imports() {
+                let owner = stacks
+                    .iter()
+                    .position(|other| other.name() == name)
+                    .ok_or_else(|| AppError::UnknownStack {
+                        stack: stack.name().to_string(),
+                        referenced: name.clone(),
+                    })?;
+                let output = sanitize(&traversal);
+                match exports[owner].get(&output) {
+                    Some(other) if *other != traversal => {
+                        return Err(AppError::Output {
+                            stack: name,
+                            output,
+                            traversals: [other.clone(), traversal],
+                        })
+                    }
+                    _ => {
+                        exports[owner].insert(output, traversal);
+                    }
+                }
+                dependencies[index].insert(owner);
+            }
+        }
+        let order = order(&dependencies).map_err(|cycle| {
+            let stacks = cycle.iter().map(|index| stacks[*index].name().to_string());
+            AppError::Cycle(stacks.collect())
+        })?;
+        for (index, exports) in exports.into_iter().enumerate() {
+            let document = &mut documents[index];
+            for (name, traversal) in exports {
+                let output = Output {
+                    value: Value::String(format!("${{{traversal}}}")),
+                    description: None,
+                    sensitive: None,
+                    depends_on: Vec::new(),
+                };
+                document.output.insert(name.clone(), output);
+                document.meta.outputs.insert(name.clone(), name);
+            }
+        }
+        for (index, dependencies) in dependencies.iter().enumerate() {
+            for owner in dependencies {
+                let remote_state = remote_state(&documents[*owner], &documents[index]);
+                let name = sanitize(&documents[*owner].meta.metadata.stack_name);
+                documents[index]
+                    .data
+                    .entry("terraform_remote_state".to_string())
+                    .or_default()
+                    .insert(name, remote_state);
+            }
+        }
+        let mut documents: Vec<_> = documents.into_iter().map(Some).collect();
+        Ok(order
+            .into_iter()
+            .filter_map(|index| documents[index].take())
+            .collect())
+    }
+}
+
+/// Returns the traversal used to read `traversal` of the stack `producer` from another stack.
+pub(crate) fn remote_reference(producer: &str, traversal: &str) -> String {
+    format!(
+        "data.terraform_remote_state.{}.outputs.{}",
+        sanitize(producer),
+        sanitize(traversal)
+    )
+}
+
+/// Replace characters not allowed in Terraform names. Different names may be mapped to the
+/// same result, which is detected by [`App::to_documents`].
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Create the `terraform_remote_state` data source used by `consumer` to read the outputs of
+/// `producer`.
+fn remote_state(producer: &Document, consumer: &Document) -> Resource {
+    let name = &producer.meta.metadata.stack_name;
+    let kind = &producer.meta.metadata.backend;
+    let mut config = producer
+        .terraform
+        .backend
+        .get(kind)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    if kind == "local" {
+        let path = config
+            .get("path")
+            .and_then(Value::as_str)
+            .unwrap_or("terraform.tfstate");
+        if std::path::Path::new(path).is_relative() {
+            let path = format!("../{name}/{path}");
+            config.insert("path".to_string(), path.into());
+        }
+    }
+    let metadata = ResourceMetadata {
+        path: format!("{}/{name}", consumer.meta.metadata.stack_name),
+        unique_id: name.clone(),
+        blocks: Some(Vec::new()),
+    };
+    Resource {
+        meta: ResourceMeta { metadata },
+        config: BTreeMap::from([
+            ("backend".to_string(), Value::String(kind.clone())),
+            ("config".to_string(), Value::Object(config)),
+        ]),
+    }
+}
+
+/// Order stacks such that every stack is placed after its `dependencies`. Independent stacks
+/// keep their order of creation. Returns the stacks forming a cycle if stacks depend on each
+/// other.
+fn order(dependencies: &[BTreeSet<usize>]) -> Result<Vec<usize>, Vec<usize>> {
+    let mut order: Vec<usize> = Vec::new();
+    while order.len() < dependencies.len() {
+        let remaining: Vec<_> = (0..dependencies.len())
+            .filter(|index| !order.contains(index))
+            .collect();
+        let next = remaining
+            .iter()
+            .find(|index| dependencies[**index].iter().all(|dep| order.contains(dep)));
+        match next {
+            Some(next) => order.push(*next),
+            None => {
+                // Every remaining stack depends on another remaining stack, so following these
+                // dependencies will eventually revisit a stack.
+                let mut path = vec![remaining[0]];
+                loop {
+                    let current = path[path.len() - 1];
+                    let dep = dependencies[current]
+                        .iter()
+                        .find(|dep| !order.contains(dep))
+                        .copied()
+                        .expect("remaining dependency");
+                    if let Some(start) = path.iter().position(|index| *index == dep) {
+                        return Err(path.split_off(start));
+                    }
+                    path.push(dep);
+                }
+            }
+        }
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::{App, AppError};
+    use crate::value::{interpolate, Cell, IntoValue, Prepare};
+    use crate::{function, Stack, Value};
+
+    /// Returns the `id` attribute of the resource `kubernetes_pod.<name>` of `stack`.
+    fn pod_id(stack: &Stack, name: &str) -> Cell<Value<String>> {
+        let prefix = stack.attribute_prefix(&format!("kubernetes_pod.{name}"));
+        Cell::<Value<String>>::new("id", "id".into_value()).prepare(format!("{prefix}.id"))
+    }
+
+    #[test]
+    fn cross_stack_reference() {
+        let app = App::new();
+        let service = app.stack("service");
+        let network = app.stack("network");
+        let name = network.local::<String>("name", "default");
+        service.local::<String>("network", &name);
+        let documents = app.to_documents().unwrap();
+        let [network, service] = documents.as_slice() else {
+            panic!("expected two documents");
+        };
+        assert_eq!(network.meta.metadata.stack_name, "network");
+        assert_eq!(network.output["local_name"].value, "${local.name}");
+        assert_eq!(
+            service.locals["network"],
+            "${data.terraform_remote_state.network.outputs.local_name}"
+        );
+        let remote_state = &service.data["terraform_remote_state"]["network"];
+        assert_eq!(remote_state.config["backend"], "local");
+        assert_eq!(
+            remote_state.config["config"]["path"],
+            "../network/terraform.tfstate"
+        );
+    }
+
+    #[test]
+    fn shadowed_reference() {
+        let app = App::new();
+        let network = app.stack("network");
+        let service = app.stack("service");
+        let name = network.local::<String>("name", "default");
+        service.local::<String>("name", "service");
+        service.local::<String>("network", &name);
+        let documents = app.to_documents().unwrap();
+        let service = &documents[1];
+        assert_eq!(service.locals["name"], "service");
+        assert_eq!(
+            service.locals["network"],
+            "${data.terraform_remote_state.network.outputs.local_name}"
+        );
+        assert_eq!(documents[0].output["local_name"].value, "${local.name}");
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let app = App::new();
+        let network = app.stack("network");
+        let dns = app.stack("dns");
+        let service = app.stack("service");
+        network.variable::<String>("region").build();
+        let region = dns.variable::<String>("region").build();
+        let own = service.variable::<String>("region").build();
+        service.local::<String>("dns_region", &region);
+        service.local::<String>("region", &own);
+        let documents = app.to_documents().unwrap();
+        let [network, dns, service] = documents.as_slice() else {
+            panic!("expected three documents");
+        };
+        assert!(network.output.is_empty());
+        assert_eq!(dns.output["var_region"].value, "${var.region}");
+        assert_eq!(
+            service.locals["dns_region"],
+            "${data.terraform_remote_state.dns.outputs.var_region}"
+        );
+        assert_eq!(service.locals["region"], "${var.region}");
+        assert_eq!(
+            service.data["terraform_remote_state"]
+                .keys()
+                .collect::<Vec<_>>(),
+            ["dns"]
+        );
+    }
+
+    #[test]
+    fn expression_reference() {
+        let app = App::new();
+        let dns = app.stack("dns");
+        let service = app.stack("service");
+        let region = dns.variable::<String>("region").build();
+        service.variable::<String>("region").build();
+        service.local::<String>("region", function::upper(&region));
+        let documents = app.to_documents().unwrap();
+        assert_eq!(
+            documents[1].locals["region"],
+            "${upper(data.terraform_remote_state.dns.outputs.var_region)}"
+        );
+        assert_eq!(documents[0].output["var_region"].value, "${var.region}");
+    }
+
+    #[test]
+    fn resource_reference() {
+        let app = App::new();
+        let network = app.stack("network");
+        let service = app.stack("service");
+        let id = pod_id(&network, "nginx");
+        assert_eq!(id.owner(), Some("network"));
+        service.local::<String>("pod", &id);
+        service.local::<String>("name", interpolate("pod-{}", &[&id]));
+        network.local::<String>("pod", &id);
+        let documents = app.to_documents().unwrap();
+        let [network, service] = documents.as_slice() else {
+            panic!("expected two documents");
+        };
+        let output = "data.terraform_remote_state.network.outputs.kubernetes_pod_nginx_id";
+        assert_eq!(service.locals["pod"], format!("${{{output}}}"));
+        assert_eq!(service.locals["name"], format!("pod-${{{output}}}"));
+        assert_eq!(network.locals["pod"], "${kubernetes_pod.nginx.id}");
+        assert_eq!(
+            network.output["kubernetes_pod_nginx_id"].value,
+            "${kubernetes_pod.nginx.id}"
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let app = App::new();
+        let a = app.stack("a");
+        let b = app.stack("b");
+        app.stack("c")
+            .local::<String>("a", &a.local::<String>("c", "c"));
+        let x = a.local::<String>("x", "x");
+        let y = b.local::<String>("y", &x);
+        a.local::<String>("z", &y);
+        let err = app.to_documents().err().unwrap();
+        assert_eq!(err, AppError::Cycle(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(err.to_string(), "stacks reference each other: a -> b -> a");
+    }
+
+    #[test]
+    fn unknown_stack() {
+        let app = App::new();
+        let other = Stack::new("other");
+        let name = other.local::<String>("name", "other");
+        app.stack("service").local::<String>("other", &name);
+        let err = app.to_documents().err().unwrap();
+        let expected = AppError::UnknownStack {
+            stack: "service".to_string(),
+            referenced: "other".to_string(),
+        };
+        assert_eq!(err, expected);
+
+        let other = Rc::new(other);
+        let network = app.stack("network");
+        other.local::<String>("network", &network.local::<String>("name", "network"));
+        other.to_document();
+        assert_eq!(
+            other.referenced_stacks().into_iter().collect::<Vec<_>>(),
+            ["network"]
+        );
+    }
+
+    #[test]
+    fn output_collision() {
+        let app = App::new();
+        let network = app.stack("network");
+        let service = app.stack("service");
+        service.local::<String>("a", &network.local::<String>("a.b", "a"));
+        service.local::<String>("b", &network.local::<String>("a_b", "b"));
+        let err = app.to_documents().err().unwrap();
+        let expected = AppError::Output {
+            stack: "network".to_string(),
+            output: "local_a_b".to_string(),
+            traversals: ["local.a.b".to_string(), "local.a_b".to_string()],
+        };
+        assert_eq!(err, expected);
+    }
+
+    #[test]
+    fn stack_name_collision() {
+        let app = App::new();
+        app.stack("a.b");
+        app.stack("a_b");
+        let err = app.to_documents().err().unwrap();
+        assert!(matches!(err, AppError::StackName { name, .. } if name == "a_b"));
+    }
+}
@@ -0,0 +1,199 @@
+use serde::Serialize;
+
+/// Used to configure where Terraform stores the state of a stack. See [`crate::Stack::backend`].
+///
+/// # Usage
+///
+/// ```rust
+/// use tf_bindgen_core::{backend::S3Backend, Stack};
+///
+/// let stack = Stack::new("nginx");
+/// stack.backend(S3Backend {
+///     bucket: "terraform-state".to_string(),
+///     key: "nginx.tfstate".to_string(),
+///     region: "eu-central-1".to_string(),
+///     encrypt: Some(true),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Clone)]
+pub enum Backend {
+    Local(LocalBackend),
+    S3(S3Backend),
+    Gcs(GcsBackend),
+    Azurerm(AzurermBackend),
+    Http(HttpBackend),
+    Pg(PgBackend),
+    Consul(ConsulBackend),
+    /// Used to configure backends not covered by this enum.
+    Custom {
+        kind: String,
+        config: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+/// See [Local Backend](https://developer.hashicorp.com/terraform/language/settings/backends/local).
+#[derive(Clone, Default, Serialize)]
+pub struct LocalBackend {
+    pub path: Option<String>,
+    pub workspace_dir: Option<String>,
+}
+
+/// See [S3 Backend](https://developer.hashicorp.com/terraform/language/settings/backends/s3).
+#[derive(Clone, Default, Serialize)]
+pub struct S3Backend {
+    pub bucket: String,
+    pub key: String,
+    pub region: String,
+    pub encrypt: Option<bool>,
+    pub dynamodb_table: Option<String>,
+    pub kms_key_id: Option<String>,
+    pub profile: Option<String>,
+    pub role_arn: Option<String>,
+    pub workspace_key_prefix: Option<String>,
+}
+
+/// See [GCS Backend](https://developer.hashicorp.com/terraform/language/settings/backends/gcs).
+#[derive(Clone, Default, Serialize)]
+pub struct GcsBackend {
+    pub bucket: String,
+    pub prefix: Option<String>,
+    pub credentials: Option<String>,
+    pub impersonate_service_account: Option<String>,
+    pub encryption_key: Option<String>,
+}
+
+/// See [AzureRM Backend](https://developer.hashicorp.com/terraform/language/settings/backends/azurerm).
+#[derive(Clone, Default, Serialize)]
+pub struct AzurermBackend {
+    pub resource_group_name: String,
+    pub storage_account_name: String,
+    pub container_name: String,
+    pub key: String,
+    pub subscription_id: Option<String>,
+    pub tenant_id: Option<String>,
+    pub use_azuread_auth: Option<bool>,
+    pub use_oidc: Option<bool>,
+}
+
+/// See [HTTP Backend](https://developer.hashicorp.com/terraform/language/settings/backends/http).
+#[derive(Clone, Default, Serialize)]
+pub struct HttpBackend {
+    pub address: String,
+    pub update_method: Option<String>,
+    pub lock_address: Option<String>,
+    pub lock_method: Option<String>,
+    pub unlock_address: Option<String>,
+    pub unlock_method: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub skip_cert_verification: Option<bool>,
+}
+
+/// See [PG Backend](https://developer.hashicorp.com/terraform/language/settings/backends/pg).
+#[derive(Clone, Default, Serialize)]
+pub struct PgBackend {
+    pub conn_str: String,
+    pub schema_name: Option<String>,
+    pub skip_schema_creation: Option<bool>,
+}
+
+/// See [Consul Backend](https://developer.hashicorp.com/terraform/language/settings/backends/consul).
+#[derive(Clone, Default, Serialize)]
+pub struct ConsulBackend {
+    pub path: String,
+    pub address: Option<String>,
+    pub scheme: Option<String>,
+    pub access_token: Option<String>,
+    pub datacenter: Option<String>,
+    pub gzip: Option<bool>,
+    pub lock: Option<bool>,
+}
+
+impl Backend {
+    /// Returns the name of the backend used by Terraform (e.g. `s3`).
+    pub fn kind(&self) -> &str {
+        match self {
+            Backend::Local(_) => "local",
+            Backend::S3(_) => "s3",
+            Backend::Gcs(_) => "gcs",
+            Backend::Azurerm(_) => "azurerm",
+            Backend::Http(_) => "http",
+            Backend::Pg(_) => "pg",
+            Backend::Consul(_) => "consul",
+            Backend::Custom { kind, .. } => kind,
+        }
+    }
+
+    /// Returns the backend configuration. Unset options will be omitted.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the backend configuration cannot be serialized to JSON.
+    pub fn to_config(&self) -> serde_json::Value {
+        let config = match self {
+            Backend::Local(backend) => serde_json::to_value(backend),
+            Backend::S3(backend) => serde_json::to_value(backend),
+            Backend::Gcs(backend) => serde_json::to_value(backend),
+            Backend::Azurerm(backend) => serde_json::to_value(backend),
+            Backend::Http(backend) => serde_json::to_value(backend),
+            Backend::Pg(backend) => serde_json::to_value(backend),
+            Backend::Consul(backend) => serde_json::to_value(backend),
+            Backend::Custom { config, .. } => return config.clone().into(),
+        };
+        match config.expect("valid backend configuration") {
+            serde_json::Value::Object(mut config) => {
+                config.retain(|_, value| !value.is_null());
+                config.into()
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+macro_rules! impl_from_backend {
+    ($variant:ident, $t:ty) => {
+        impl From<$t> for Backend {
+            fn from(backend: $t) -> Self {
+                Backend::$variant(backend)
+            }
+        }
+    };
+}
+
+impl_from_backend!(Local, LocalBackend);
+impl_from_backend!(S3, S3Backend);
+impl_from_backend!(Gcs, GcsBackend);
+impl_from_backend!(Azurerm, AzurermBackend);
+impl_from_backend!(Http, HttpBackend);
+impl_from_backend!(Pg, PgBackend);
+impl_from_backend!(Consul, ConsulBackend);
+
+#[cfg(test)]
+mod tests {
+    use super::{Backend, PgBackend};
+
+    #[test]
+    fn backend_skip_unset() {
+        let backend: Backend = PgBackend {
+            conn_str: "postgres://localhost/terraform".to_string(),
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(backend.kind(), "pg");
+        let expected = serde_json::json!({ "conn_str": "postgres://localhost/terraform" });
+        assert_eq!(backend.to_config(), expected);
+    }
+
+    #[test]
+    fn backend_custom() {
+        let mut config = serde_json::Map::new();
+        config.insert("organization".to_string(), "acme".into());
+        let backend = Backend::Custom {
+            kind: "remote".to_string(),
+            config,
+        };
+        assert_eq!(backend.kind(), "remote");
+        assert_eq!(backend.to_config()["organization"], "acme");
+    }
+}
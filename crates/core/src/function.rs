@@ -0,0 +1,181 @@
+//! Typed wrappers around Terraform's built-in functions. Every function returns a reference to
+//! the function call expression, which can be passed to setters of resources, outputs and local
+//! values.
+//!
+//! ```rust
+//! use tf_bindgen_core::function;
+//! use tf_bindgen_core::value::IntoValue;
+//! use tf_bindgen_core::Value;
+//!
+//! let name: Value<String> = function::join("-", ["nginx", "web"]);
+//! let name = serde_json::to_value(name).unwrap();
+//! assert_eq!(name, r#"${join("-", ["nginx", "web"])}"#);
+//! ```
+
+use serde::Serialize;
+
+use crate::value::{IntoValue, IntoValueList, ToExpression};
+use crate::Value;
+
+fn call<T: Serialize>(name: &str, args: &[&dyn ToExpression]) -> Value<T> {
+    let args: Vec<_> = args.iter().map(|arg| arg.to_expression()).collect();
+    Value::from_expression(format!("{name}({})", args.join(", ")))
+}
+
+/// See [`format`](https://developer.hashicorp.com/terraform/language/functions/format).
+pub fn format(spec: impl IntoValue<String>, args: &[&dyn ToExpression]) -> Value<String> {
+    let spec = spec.into_value();
+    let mut all: Vec<&dyn ToExpression> = vec![&spec];
+    all.extend_from_slice(args);
+    call("format", &all)
+}
+
+/// See [`join`](https://developer.hashicorp.com/terraform/language/functions/join).
+pub fn join(separator: impl IntoValue<String>, list: impl ToExpression) -> Value<String> {
+    call("join", &[&separator.into_value(), &list])
+}
+
+/// See [`lookup`](https://developer.hashicorp.com/terraform/language/functions/lookup).
+pub fn lookup<T: Serialize>(
+    map: impl ToExpression,
+    key: impl IntoValue<String>,
+    default: impl IntoValue<T>,
+) -> Value<T> {
+    call("lookup", &[&map, &key.into_value(), &default.into_value()])
+}
+
+/// See [`element`](https://developer.hashicorp.com/terraform/language/functions/element).
+pub fn element<T: Serialize>(list: impl ToExpression, index: impl IntoValue<i64>) -> Value<T> {
+    call("element", &[&list, &index.into_value()])
+}
+
+/// See [`length`](https://developer.hashicorp.com/terraform/language/functions/length).
+pub fn length(value: impl ToExpression) -> Value<i64> {
+    call("length", &[&value])
+}
+
+/// See [`cidrsubnet`](https://developer.hashicorp.com/terraform/language/functions/cidrsubnet).
+pub fn cidrsubnet(
+    prefix: impl IntoValue<String>,
+    newbits: impl IntoValue<i64>,
+    netnum: impl IntoValue<i64>,
+) -> Value<String> {
+    call(
+        "cidrsubnet",
+        &[
+            &prefix.into_value(),
+            &newbits.into_value(),
+            &netnum.into_value(),
+        ],
+    )
+}
+
+/// See [`cidrhost`](https://developer.hashicorp.com/terraform/language/functions/cidrhost).
+pub fn cidrhost(prefix: impl IntoValue<String>, hostnum: impl IntoValue<i64>) -> Value<String> {
+    call("cidrhost", &[&prefix.into_value(), &hostnum.into_value()])
+}
+
+/// See [`base64encode`](https://developer.hashicorp.com/terraform/language/functions/base64encode).
+pub fn base64encode(value: impl IntoValue<String>) -> Value<String> {
+    call("base64encode", &[&value.into_value()])
+}
+
+/// See [`base64decode`](https://developer.hashicorp.com/terraform/language/functions/base64decode).
+pub fn base64decode(value: impl IntoValue<String>) -> Value<String> {
+    call("base64decode", &[&value.into_value()])
+}
+
+/// See [`jsonencode`](https://developer.hashicorp.com/terraform/language/functions/jsonencode).
+pub fn jsonencode(value: impl ToExpression) -> Value<String> {
+    call("jsonencode", &[&value])
+}
+
+/// See [`lower`](https://developer.hashicorp.com/terraform/language/functions/lower).
+pub fn lower(value: impl IntoValue<String>) -> Value<String> {
+    call("lower", &[&value.into_value()])
+}
+
+/// See [`upper`](https://developer.hashicorp.com/terraform/language/functions/upper).
+pub fn upper(value: impl IntoValue<String>) -> Value<String> {
+    call("upper", &[&value.into_value()])
+}
+
+/// See [`trimspace`](https://developer.hashicorp.com/terraform/language/functions/trimspace).
+pub fn trimspace(value: impl IntoValue<String>) -> Value<String> {
+    call("trimspace", &[&value.into_value()])
+}
+
+/// See [`replace`](https://developer.hashicorp.com/terraform/language/functions/replace).
+pub fn replace(
+    value: impl IntoValue<String>,
+    substring: impl IntoValue<String>,
+    replacement: impl IntoValue<String>,
+) -> Value<String> {
+    call(
+        "replace",
+        &[
+            &value.into_value(),
+            &substring.into_value(),
+            &replacement.into_value(),
+        ],
+    )
+}
+
+/// See [`tostring`](https://developer.hashicorp.com/terraform/language/functions/tostring).
+pub fn tostring(value: impl ToExpression) -> Value<String> {
+    call("tostring", &[&value])
+}
+
+/// See [`tonumber`](https://developer.hashicorp.com/terraform/language/functions/tonumber).
+pub fn tonumber(value: impl ToExpression) -> Value<i64> {
+    call("tonumber", &[&value])
+}
+
+/// See [`min`](https://developer.hashicorp.com/terraform/language/functions/min).
+pub fn min(values: impl IntoValueList<i64>) -> Value<i64> {
+    let values = values.into_value_list();
+    let args: Vec<&dyn ToExpression> = values.iter().map(|v| v as &dyn ToExpression).collect();
+    call("min", &args)
+}
+
+/// See [`max`](https://developer.hashicorp.com/terraform/language/functions/max).
+pub fn max(values: impl IntoValueList<i64>) -> Value<i64> {
+    let values = values.into_value_list();
+    let args: Vec<&dyn ToExpression> = values.iter().map(|v| v as &dyn ToExpression).collect();
+    call("max", &args)
+}
+
+/// Create a conditional expression (i.e. `condition ? true_value : false_value`).
+pub fn conditional<T: Serialize>(
+    condition: impl IntoValue<bool>,
+    true_value: impl IntoValue<T>,
+    false_value: impl IntoValue<T>,
+) -> Value<T> {
+    let condition = condition.into_value().to_expression();
+    let true_value = true_value.into_value().to_expression();
+    let false_value = false_value.into_value().to_expression();
+    Value::from_expression(format!("({condition} ? {true_value} : {false_value})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::{Cell, IntoValue};
+    use crate::{function, Stack, Value};
+
+    #[test]
+    fn function_of_reference() {
+        let id: Cell<Value<String>> = Cell::new("kubernetes_pod.nginx.id", "".into_value());
+        let name = function::format("%s-%d", &[&id, &3]);
+        let name = serde_json::to_value(name).unwrap();
+        assert_eq!(name, r#"${format("%s-%d", kubernetes_pod.nginx.id, 3)}"#);
+    }
+
+    #[test]
+    fn conditional_of_variable() {
+        let stack = Stack::new("test");
+        let production = stack.variable::<bool>("production").build();
+        let replicas = function::conditional(production, 3, 1) + 1;
+        let replicas = serde_json::to_value(replicas).unwrap();
+        assert_eq!(replicas, "${((var.production ? 3 : 1) + 1)}");
+    }
+}
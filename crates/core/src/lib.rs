@@ -1,10 +1,26 @@
+mod app;
+pub mod backend;
+pub mod function;
+mod meta;
+mod output;
 mod path;
+mod reference;
 mod stack;
+pub mod value;
+mod variable;
 
 use ::tf_bindgen_schema::document::Resource;
 
+pub use crate::app::{App, AppError};
+pub use crate::backend::Backend;
+pub use crate::meta::{
+    Condition, ForEach, IntoForEach, Lifecycle, LifecycleBuilder, MetaArguments,
+};
+pub use crate::output::OutputBuilder;
 pub use crate::path::Path;
 pub use crate::stack::Stack;
+pub use crate::value::Value;
+pub use crate::variable::{VariableBuilder, VariableType};
 
 pub trait Scope {
     /// Returns the stack an object is associated with.
@@ -17,9 +33,36 @@ pub trait Scope {
 pub trait L1Construct: Scope {
     /// Returns the resource type and configuration of this construct.
     fn to_schema(&self) -> (String, Resource);
+
+    /// Returns the reference used by Terraform to address this construct (e.g.
+    /// `kubernetes_pod.<id>`).
+    fn reference(&self) -> String {
+        let (ty, _) = self.to_schema();
+        format!("{ty}.{}", self.path().id())
+    }
 }
 
 pub trait Provider: Scope {
     /// Returns the provider version and configuration.
     fn to_schema(&self) -> (String, tf_bindgen_schema::document::Provider);
+
+    /// Returns the alias of this provider configuration.
+    fn alias(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the local name of this provider (e.g. `kubernetes`).
+    fn local_name(&self) -> String {
+        let path = self.path();
+        path.name().split('/').next_back().unwrap().to_string()
+    }
+
+    /// Returns the reference used by Terraform to select this provider configuration (e.g.
+    /// `kubernetes.west`).
+    fn reference(&self) -> String {
+        match self.alias() {
+            Some(alias) => format!("{}.{alias}", self.local_name()),
+            None => self.local_name(),
+        }
+    }
 }
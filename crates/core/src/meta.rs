@@ -0,0 +1,344 @@
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+use crate::reference;
+use crate::value::{IntoValue, ValueSet};
+use crate::{L1Construct, Provider, Value};
+
+/// Used to store the meta-arguments of a resource or data source.
+#[derive(Clone, Default, Serialize)]
+pub struct MetaArguments {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<Value<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    for_each: Option<ForEach>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lifecycle: Option<Lifecycle>,
+}
+
+/// Used to store the `for_each` meta-argument, which is either a set of strings or a map (see
+/// [`IntoForEach`]).
+#[derive(Clone)]
+pub struct ForEach(Rc<dyn Fn() -> serde_json::Result<serde_json::Value>>);
+
+/// Implemented by the values accepted by the `for_each` meta-argument, i.e. sets of strings and
+/// maps.
+pub trait IntoForEach {
+    fn into_for_each(self) -> ForEach;
+}
+
+/// Used to store the `lifecycle` meta-argument of a resource. See [`Lifecycle::builder`].
+#[derive(Clone, Default, Serialize)]
+pub struct Lifecycle {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    create_before_destroy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prevent_destroy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ignore_changes: Option<IgnoreChanges>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    replace_triggered_by: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    precondition: Vec<Condition>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    postcondition: Vec<Condition>,
+}
+
+#[derive(Clone, Default)]
+pub struct LifecycleBuilder {
+    lifecycle: Lifecycle,
+}
+
+#[derive(Clone)]
+enum IgnoreChanges {
+    All,
+    Attributes(Vec<String>),
+}
+
+/// Used to store a custom condition of a resource (see [`LifecycleBuilder::precondition`]).
+#[derive(Clone, Serialize)]
+pub struct Condition {
+    #[serde(serialize_with = "reference::serialize_marked")]
+    condition: String,
+    error_message: String,
+}
+
+impl MetaArguments {
+    /// Add `constructs` to the explicit dependencies.
+    pub fn depends_on(&mut self, constructs: &[&dyn L1Construct]) {
+        self.depends_on
+            .extend(constructs.iter().map(|construct| construct.reference()))
+    }
+
+    /// Set the number of instances to create.
+    pub fn count(&mut self, count: impl IntoValue<i64>) {
+        self.count = Some(count.into_value())
+    }
+
+    /// Set the map or set of strings used to create one instance per element.
+    pub fn for_each(&mut self, for_each: impl IntoForEach) {
+        self.for_each = Some(for_each.into_for_each())
+    }
+
+    /// Set the provider configuration to use. Will use the alias of `provider` if set.
+    pub fn provider(&mut self, provider: &dyn Provider) {
+        self.provider = Some(provider.reference())
+    }
+
+    /// Set the lifecycle meta-argument.
+    pub fn lifecycle(&mut self, lifecycle: Lifecycle) {
+        self.lifecycle = Some(lifecycle)
+    }
+
+    /// Insert the configured meta-arguments into `config`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if meta-arguments cannot be serialized to JSON.
+    pub fn extend_config(&self, config: &mut BTreeMap<String, serde_json::Value>) {
+        match serde_json::to_value(self).expect("valid meta-arguments") {
+            serde_json::Value::Object(meta) => config.extend(meta),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl ForEach {
+    fn new<T: Serialize + 'static>(value: Value<T>) -> Self {
+        // Serialized on demand, so references are resolved by the stack using them.
+        Self(Rc::new(move || serde_json::to_value(&value)))
+    }
+}
+
+impl Serialize for ForEach {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (self.0)().map_err(S::Error::custom)?.serialize(serializer)
+    }
+}
+
+impl<T: IntoValue<ValueSet<String>>> IntoForEach for T {
+    fn into_for_each(self) -> ForEach {
+        ForEach::new(self.into_value())
+    }
+}
+
+impl<T: Serialize + 'static> IntoForEach for HashMap<String, T> {
+    fn into_for_each(self) -> ForEach {
+        ForEach::new(Value::Value {
+            value: Rc::new(self),
+        })
+    }
+}
+
+impl<T: Serialize + 'static> IntoForEach for BTreeMap<String, T> {
+    fn into_for_each(self) -> ForEach {
+        ForEach::new(Value::Value {
+            value: Rc::new(self),
+        })
+    }
+}
+
+impl<T: Serialize + 'static> IntoForEach for Value<HashMap<String, T>> {
+    fn into_for_each(self) -> ForEach {
+        ForEach::new(self)
+    }
+}
+
+impl<T: Serialize + 'static> IntoForEach for Value<BTreeMap<String, T>> {
+    fn into_for_each(self) -> ForEach {
+        ForEach::new(self)
+    }
+}
+
+impl<T: Clone + Serialize + 'static> IntoForEach for &Value<HashMap<String, T>> {
+    fn into_for_each(self) -> ForEach {
+        ForEach::new(self.clone())
+    }
+}
+
+impl<T: Clone + Serialize + 'static> IntoForEach for &Value<BTreeMap<String, T>> {
+    fn into_for_each(self) -> ForEach {
+        ForEach::new(self.clone())
+    }
+}
+
+impl Lifecycle {
+    pub fn builder() -> LifecycleBuilder {
+        LifecycleBuilder::default()
+    }
+}
+
+impl LifecycleBuilder {
+    /// Create the replacement object before destroying the current one.
+    pub fn create_before_destroy(&mut self, value: bool) -> &mut Self {
+        self.lifecycle.create_before_destroy = Some(value);
+        self
+    }
+
+    /// Reject plans which would destroy this object.
+    pub fn prevent_destroy(&mut self, value: bool) -> &mut Self {
+        self.lifecycle.prevent_destroy = Some(value);
+        self
+    }
+
+    /// Ignore changes of the specified attributes (e.g. `metadata[0].labels`).
+    pub fn ignore_changes<I, S>(&mut self, attributes: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let attributes = attributes.into_iter().map(Into::into);
+        match &mut self.lifecycle.ignore_changes {
+            Some(IgnoreChanges::Attributes(list)) => list.extend(attributes),
+            Some(IgnoreChanges::All) => {}
+            None => {
+                self.lifecycle.ignore_changes =
+                    Some(IgnoreChanges::Attributes(attributes.collect()))
+            }
+        }
+        self
+    }
+
+    /// Ignore changes of all attributes.
+    pub fn ignore_all_changes(&mut self) -> &mut Self {
+        self.lifecycle.ignore_changes = Some(IgnoreChanges::All);
+        self
+    }
+
+    /// Replace this object if `construct` was replaced.
+    pub fn replace_triggered_by(&mut self, construct: &dyn L1Construct) -> &mut Self {
+        self.lifecycle
+            .replace_triggered_by
+            .push(construct.reference());
+        self
+    }
+
+    /// Replace this object if the referenced attribute has changed.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `attribute` is not a reference.
+    pub fn replace_triggered_by_attribute<T>(&mut self, attribute: impl IntoValue<T>) -> &mut Self {
+        match attribute.into_value() {
+            Value::Ref { path, .. } => self.lifecycle.replace_triggered_by.push(path),
+            Value::Value { .. } | Value::Template { .. } => {
+                panic!("`replace_triggered_by` expects a reference")
+            }
+        }
+        self
+    }
+
+    /// Add a condition checked before creating this object. `condition` is a Terraform
+    /// expression without surrounding `${...}` (e.g. `self.replicas > 0`).
+    pub fn precondition(
+        &mut self,
+        condition: impl Into<String>,
+        error_message: impl Into<String>,
+    ) -> &mut Self {
+        self.lifecycle.precondition.push(Condition {
+            condition: format!("${{{}}}", condition.into()),
+            error_message: error_message.into(),
+        });
+        self
+    }
+
+    /// Add a condition checked after creating this object. `condition` is a Terraform
+    /// expression without surrounding `${...}` (e.g. `self.replicas > 0`).
+    pub fn postcondition(
+        &mut self,
+        condition: impl Into<String>,
+        error_message: impl Into<String>,
+    ) -> &mut Self {
+        self.lifecycle.postcondition.push(Condition {
+            condition: format!("${{{}}}", condition.into()),
+            error_message: error_message.into(),
+        });
+        self
+    }
+
+    pub fn build(&mut self) -> Lifecycle {
+        self.lifecycle.clone()
+    }
+}
+
+impl Serialize for IgnoreChanges {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            IgnoreChanges::All => serializer.serialize_str("all"),
+            IgnoreChanges::Attributes(attributes) => attributes.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use crate::value::{IntoValueSet, ValueSet};
+    use crate::{Stack, Value};
+
+    use super::{Lifecycle, MetaArguments};
+
+    #[test]
+    fn meta_arguments_empty() {
+        let mut config = BTreeMap::new();
+        MetaArguments::default().extend_config(&mut config);
+        assert!(config.is_empty());
+    }
+
+    #[test]
+    fn meta_arguments_lifecycle() {
+        let mut meta = MetaArguments::default();
+        meta.count(3);
+        meta.lifecycle(
+            Lifecycle::builder()
+                .ignore_changes(["metadata"])
+                .ignore_all_changes()
+                .postcondition("self.id != \"\"", "missing id")
+                .build(),
+        );
+        let mut config = BTreeMap::new();
+        meta.extend_config(&mut config);
+        assert_eq!(config["count"], 3);
+        let expected = serde_json::json!({
+            "ignore_changes": "all",
+            "postcondition": [{ "condition": "${self.id != \"\"}", "error_message": "missing id" }]
+        });
+        assert_eq!(config["lifecycle"], expected);
+    }
+
+    #[test]
+    fn meta_arguments_for_each() {
+        let for_each = |meta: &MetaArguments| {
+            let mut config = BTreeMap::new();
+            meta.extend_config(&mut config);
+            config["for_each"].clone()
+        };
+        let mut meta = MetaArguments::default();
+        meta.for_each(["b", "a", "${b}"].into_value_set());
+        assert_eq!(for_each(&meta), serde_json::json!(["$${b}", "a", "b"]));
+        meta.for_each(HashMap::from([("a".to_string(), 1)]));
+        assert_eq!(for_each(&meta), serde_json::json!({ "a": 1 }));
+
+        let stack = Stack::new("stack");
+        let names: Value<ValueSet<String>> = stack.variable("names").build();
+        meta.for_each(&names);
+        assert_eq!(for_each(&meta), "${var.names}");
+        let labels: Value<BTreeMap<String, String>> = stack.variable("labels").build();
+        meta.for_each(&labels);
+        assert_eq!(for_each(&meta), "${var.labels}");
+    }
+}
@@ -0,0 +1,91 @@
+use serde::Serialize;
+use tf_bindgen_schema::document;
+
+use crate::value::IntoValue;
+use crate::{L1Construct, Stack};
+
+/// Used to configure a Terraform output of a stack. See [`Stack::add_output`].
+pub struct OutputBuilder {
+    stack: Stack,
+    name: String,
+    output: document::Output,
+}
+
+impl OutputBuilder {
+    pub(crate) fn new(stack: Stack, name: String, value: serde_json::Value) -> Self {
+        Self {
+            stack,
+            name,
+            output: document::Output {
+                value,
+                description: None,
+                sensitive: None,
+                depends_on: Vec::new(),
+            },
+        }
+    }
+
+    /// Set the description of this output.
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.output.description = Some(description.into());
+        self
+    }
+
+    /// Mark this output as sensitive. Terraform will not show sensitive values in its CLI output.
+    pub fn sensitive(&mut self, sensitive: bool) -> &mut Self {
+        self.output.sensitive = Some(sensitive);
+        self
+    }
+
+    /// Add `constructs` to the explicit dependencies of this output.
+    pub fn depends_on(&mut self, constructs: &[&dyn L1Construct]) -> &mut Self {
+        self.output
+            .depends_on
+            .extend(constructs.iter().map(|construct| construct.reference()));
+        self
+    }
+
+    /// Add the configured output to the stack.
+    pub fn build(&mut self) {
+        self.stack
+            .insert_output(self.name.clone(), self.output.clone())
+    }
+}
+
+impl Stack {
+    /// Create a new output `name` of this stack with `value` as content. The output will only be
+    /// added after calling [`OutputBuilder::build`]. References (e.g. `&pod.metadata[0].name`)
+    /// are written as references, literals as is.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `value` cannot be serialized to JSON.
+    pub fn add_output<T: Serialize>(
+        &self,
+        name: impl Into<String>,
+        value: impl IntoValue<T>,
+    ) -> OutputBuilder {
+        let value = value.into_value();
+        let value = self.consume(|| serde_json::to_value(value).expect("valid output value"));
+        OutputBuilder::new(self.clone(), name.into(), value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::{Cell, Computed};
+    use crate::{Stack, Value};
+
+    #[test]
+    fn output_of_computed_field() {
+        let stack = Stack::new("test");
+        let cell: Cell<Computed<Value<String>>> =
+            Cell::new("kubernetes_pod.nginx.id", Computed::default());
+        stack.add_output("pod_id", &cell).build();
+        let document = stack.to_document();
+        assert_eq!(
+            document.output["pod_id"].value,
+            "${kubernetes_pod.nginx.id}"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Used to resolve references to objects of other stacks. Every reference records the stack
+//! declaring the referenced object (see [`crate::Value::Ref`]). Inside of expressions and
+//! templates, such a reference is marked using [`mark`], so the owner is preserved when
+//! expressions are combined (e.g. using [`crate::function`]).
+//!
+//! References and marked expressions are resolved once they are serialized into the
+//! configuration of a stack (see [`Stack::consume`]): references to the same stack are written
+//! as is, references to other stacks are recorded and replaced by an output of the remote state
+//! of the referenced stack (see [`crate::App::to_documents`]).
+
+use std::cell::RefCell;
+
+use serde::Serializer;
+
+use crate::app::remote_reference;
+use crate::Stack;
+
+const MARK_START: char = '\u{2}';
+const MARK_SEPARATOR: char = '\u{1f}';
+const MARK_END: char = '\u{3}';
+
+/// Target of the references currently serialized.
+enum Context {
+    /// References are serialized into the configuration of this stack.
+    Stack(Stack),
+    /// References are serialized as part of an expression, so their owners must be preserved.
+    Expression,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+/// Used to restore the previous context after serializing.
+struct ContextGuard(Option<Context>);
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        CONTEXT.with(|context| *context.borrow_mut() = self.0.take());
+    }
+}
+
+fn with_context<R>(context: Context, serialize: impl FnOnce() -> R) -> R {
+    let previous = CONTEXT.with(|current| current.replace(Some(context)));
+    let _guard = ContextGuard(previous);
+    serialize()
+}
+
+/// Serialize references using `serialize` as part of the configuration of `stack`.
+pub(crate) fn with_stack<R>(stack: &Stack, serialize: impl FnOnce() -> R) -> R {
+    with_context(Context::Stack(stack.clone()), serialize)
+}
+
+/// Serialize references using `serialize` as part of an expression. Owners of references are
+/// preserved using [`mark`].
+pub(crate) fn with_expression<R>(serialize: impl FnOnce() -> R) -> R {
+    with_context(Context::Expression, serialize)
+}
+
+/// Returns `path` marked as declared by the stack `owner`.
+pub(crate) fn mark(owner: &str, path: &str) -> String {
+    format!("{MARK_START}{owner}{MARK_SEPARATOR}{path}{MARK_END}")
+}
+
+/// Returns `true` if `c` is used to mark references (see [`mark`]).
+pub(crate) fn is_mark(c: char) -> bool {
+    [MARK_START, MARK_SEPARATOR, MARK_END].contains(&c)
+}
+
+/// Split `path` starting with a marked path (see [`mark`]) into owner and plain path. Text
+/// following the marked path is appended to the returned path (e.g. `<marked>.metadata`).
+pub(crate) fn unmark(path: String) -> (Option<String>, String) {
+    let split = path.strip_prefix(MARK_START).and_then(|marked| {
+        let (owner, rest) = marked.split_once(MARK_SEPARATOR)?;
+        let (path, suffix) = rest.split_once(MARK_END)?;
+        Some((owner.to_string(), format!("{path}{suffix}")))
+    });
+    match split {
+        Some((owner, path)) => (Some(owner), path),
+        None => (None, path),
+    }
+}
+
+/// Returns the traversal used to reference `path` declared by the stack `owner`.
+pub(crate) fn resolve(owner: Option<&str>, path: &str) -> String {
+    let Some(owner) = owner else {
+        return resolve_marks(path);
+    };
+    CONTEXT.with(|context| match context.borrow().as_ref() {
+        Some(Context::Stack(stack)) if stack.name() != owner => {
+            stack.import(owner, path);
+            remote_reference(owner, path)
+        }
+        Some(Context::Expression) => mark(owner, path),
+        _ => path.to_string(),
+    })
+}
+
+/// Serialize the expression or template `text` with all marked references resolved (see
+/// [`resolve_marks`]).
+pub(crate) fn serialize_marked<S: Serializer>(
+    text: &str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&resolve_marks(text))
+}
+
+/// Resolve all marked references (see [`mark`]) in the expression or template `text`.
+pub(crate) fn resolve_marks(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(MARK_START) {
+        result += &rest[..start];
+        let marked = &rest[start + MARK_START.len_utf8()..];
+        let parts = marked.split_once(MARK_END).and_then(|(marked, next)| {
+            let (owner, path) = marked.split_once(MARK_SEPARATOR)?;
+            Some((owner, path, next))
+        });
+        let Some((owner, path, next)) = parts else {
+            result.push(MARK_START);
+            rest = marked;
+            continue;
+        };
+        result += &resolve(Some(owner), path);
+        rest = next;
+    }
+    result + rest
+}
@@ -1,12 +1,16 @@
 use std::borrow::{Borrow, BorrowMut};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::rc::Rc;
 
-use tf_bindgen_schema::document::{Meta, Metadata, ProviderConfig, Terraform};
+use serde::Serialize;
+use tf_bindgen_schema::document::{Meta, Metadata, Output, ProviderConfig, Terraform, Variable};
 use tf_bindgen_schema::Document;
 
-use crate::{L1Construct, Path, Provider, Scope};
+use crate::backend::Backend;
+use crate::reference;
+use crate::value::IntoValue;
+use crate::{L1Construct, Path, Provider, Scope, Value};
 
 /// Used to store and manage all resources and data sources associated with an infrastructure
 /// deployment.
@@ -22,6 +26,12 @@ struct InnerStack {
     provider: RefCell<Vec<Rc<dyn Provider>>>,
     resources: RefCell<Vec<Rc<dyn L1Construct>>>,
     data_sources: RefCell<Vec<Rc<dyn L1Construct>>>,
+    outputs: RefCell<Vec<(String, Output)>>,
+    variables: RefCell<Vec<(String, Variable)>>,
+    locals: RefCell<Vec<(String, serde_json::Value)>>,
+    backend: RefCell<Option<Backend>>,
+    /// Objects of other stacks referenced by this stack (as pairs of stack name and traversal).
+    imports: RefCell<BTreeSet<(String, String)>>,
 }
 
 impl Stack {
@@ -32,6 +42,11 @@ impl Stack {
                 provider: RefCell::new(Vec::new()),
                 resources: RefCell::new(Vec::new()),
                 data_sources: RefCell::new(Vec::new()),
+                outputs: RefCell::new(Vec::new()),
+                variables: RefCell::new(Vec::new()),
+                locals: RefCell::new(Vec::new()),
+                backend: RefCell::new(None),
+                imports: RefCell::new(BTreeSet::new()),
             }),
         })
     }
@@ -41,6 +56,12 @@ impl Stack {
         &self.inner.name
     }
 
+    /// Set the backend used to store the Terraform state of this stack. Will use the `local`
+    /// backend if not set.
+    pub fn backend(&self, backend: impl Into<Backend>) {
+        *self.inner.backend.borrow_mut() = Some(backend.into())
+    }
+
     /// Add `provider` to this stack.
     pub fn add_provider(&self, provider: Rc<dyn Provider>) {
         self.inner.provider.borrow_mut().push(provider)
@@ -56,8 +77,80 @@ impl Stack {
         self.inner.data_sources.borrow_mut().push(data_source)
     }
 
-    /// Generate Terraform JSON configuration out of stored provider, resources and data sources.
+    /// Add a local value `name` to this stack. Returns a reference to the local value (i.e.
+    /// `${local.<name>}`), which can be used instead of repeating `value` in every resource.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `value` cannot be serialized to JSON.
+    pub fn local<T: Serialize>(
+        &self,
+        name: impl Into<String>,
+        value: impl IntoValue<T>,
+    ) -> Value<T> {
+        let name = name.into();
+        let value = value.into_value();
+        let config = self.consume(|| serde_json::to_value(&value).expect("valid local value"));
+        self.inner.locals.borrow_mut().push((name.clone(), config));
+        Value::Ref {
+            path: format!("local.{name}"),
+            value: Some(Box::new(value)),
+            owner: Some(self.name().to_string()),
+        }
+    }
+
+    /// Returns the prefix used to prepare the attributes of the object `reference` declared by
+    /// this stack (see [`crate::value::Prepare`]). References to these attributes will record
+    /// this stack as their owner.
+    pub fn attribute_prefix(&self, reference: &str) -> String {
+        reference::mark(self.name(), reference)
+    }
+
+    /// Returns the names of the other stacks referenced by this stack. References are
+    /// recorded while generating the configuration (see [`Stack::to_document`]). Such a stack
+    /// can only be synthesized as part of an [`crate::App`].
+    pub fn referenced_stacks(&self) -> BTreeSet<String> {
+        let imports = self.inner.imports.borrow();
+        imports.iter().map(|(stack, _)| stack.clone()).collect()
+    }
+
+    /// Returns the objects of other stacks referenced by this stack as pairs of stack name and
+    /// traversal (e.g. `("network", "local.name")`).
+    pub(crate) fn imports(&self) -> Vec<(String, String)> {
+        self.inner.imports.borrow().iter().cloned().collect()
+    }
+
+    /// Record a reference to `traversal` of the stack `owner`.
+    pub(crate) fn import(&self, owner: &str, traversal: &str) {
+        self.inner
+            .imports
+            .borrow_mut()
+            .insert((owner.to_string(), traversal.to_string()));
+    }
+
+    /// Serialize a configuration of this stack using `serialize`. References to other stacks
+    /// are recorded and resolved (see [`crate::reference`]).
+    pub(crate) fn consume<R>(&self, serialize: impl FnOnce() -> R) -> R {
+        reference::with_stack(self, serialize)
+    }
+
+    /// Add `output` named `name` to the output store. See [`Stack::add_output`].
+    pub(crate) fn insert_output(&self, name: String, output: Output) {
+        self.inner.outputs.borrow_mut().push((name, output))
+    }
+
+    /// Add `variable` named `name` to the variable store. See [`Stack::variable`].
+    pub(crate) fn insert_variable(&self, name: String, variable: Variable) {
+        self.inner.variables.borrow_mut().push((name, variable))
+    }
+
+    /// Generate Terraform JSON configuration out of stored provider, resources, data sources,
+    /// outputs, variables and local values.
     pub fn to_document(&self) -> Document {
+        self.consume(|| self.build_document())
+    }
+
+    fn build_document(&self) -> Document {
         let mut document = Document {
             meta: Meta {
                 metadata: Metadata {
@@ -65,20 +158,45 @@ impl Stack {
                     stack_name: self.name().to_string(),
                     version: tf_bindgen_schema::VERSION.to_string(),
                 },
-                outputs: HashMap::default(),
+                outputs: BTreeMap::default(),
             },
             terraform: Terraform {
-                required_providers: HashMap::default(),
+                required_providers: BTreeMap::default(),
+                backend: BTreeMap::default(),
             },
-            provider: HashMap::default(),
-            resource: HashMap::default(),
-            data: HashMap::default(),
+            provider: BTreeMap::default(),
+            resource: BTreeMap::default(),
+            data: BTreeMap::default(),
+            output: BTreeMap::default(),
+            variable: BTreeMap::default(),
+            locals: BTreeMap::default(),
         };
+        if let Some(backend) = self.inner.backend.borrow().as_ref() {
+            document.meta.metadata.backend = backend.kind().to_string();
+            document
+                .terraform
+                .backend
+                .insert(backend.kind().to_string(), backend.to_config());
+        }
+        let mut provider_refs = HashSet::new();
         for provider in self.inner.provider.borrow().iter() {
             let path = provider.path();
             let name = path.name();
             let local_name = name.split('/').last().unwrap();
-            let (version, schema) = provider.to_schema();
+            if !provider_refs.insert(provider.reference()) {
+                match provider.alias() {
+                    Some(alias) => {
+                        panic!("provider '{local_name}' with alias '{alias}' already exists")
+                    }
+                    None => {
+                        panic!("provider '{local_name}' is configured multiple times without alias")
+                    }
+                }
+            }
+            let (version, mut schema) = provider.to_schema();
+            if let Some(alias) = provider.alias() {
+                schema.insert("alias".to_string(), alias.into());
+            }
             let config = ProviderConfig {
                 source: name.to_string(),
                 version,
@@ -105,7 +223,7 @@ impl Stack {
             let key = path.id();
             let (ty, schema) = resource.to_schema();
             if !document.resource.contains_key(&ty) {
-                document.resource.insert(ty.clone(), HashMap::new());
+                document.resource.insert(ty.clone(), BTreeMap::new());
             }
             if document
                 .resource
@@ -122,7 +240,7 @@ impl Stack {
             let key = path.id();
             let (ty, schema) = data_source.to_schema();
             if !document.data.contains_key(&ty) {
-                document.data.insert(ty.clone(), HashMap::new());
+                document.data.insert(ty.clone(), BTreeMap::new());
             }
             if document
                 .data
@@ -134,6 +252,34 @@ impl Stack {
                 panic!("data source '{path}' already exists");
             }
         }
+        for (name, output) in self.inner.outputs.borrow().iter() {
+            if document
+                .output
+                .insert(name.clone(), output.clone())
+                .is_some()
+            {
+                panic!("output '{name}' already exists");
+            }
+            document.meta.outputs.insert(name.clone(), name.clone());
+        }
+        for (name, variable) in self.inner.variables.borrow().iter() {
+            if document
+                .variable
+                .insert(name.clone(), variable.clone())
+                .is_some()
+            {
+                panic!("variable '{name}' already exists");
+            }
+        }
+        for (name, value) in self.inner.locals.borrow().iter() {
+            if document
+                .locals
+                .insert(name.clone(), value.clone())
+                .is_some()
+            {
+                panic!("local value '{name}' already exists");
+            }
+        }
         document
     }
 }
@@ -148,3 +294,197 @@ impl Scope for Stack {
         Path::from(self.name().to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use tf_bindgen_schema::document::{Resource, ResourceMeta, ResourceMetadata};
+
+    use crate::backend::GcsBackend;
+    use crate::{L1Construct, Path, Provider, Scope, Stack, Value};
+
+    struct TestProvider {
+        stack: Stack,
+        alias: Option<String>,
+    }
+
+    impl TestProvider {
+        fn create(stack: &Stack, alias: Option<&str>) -> Rc<Self> {
+            let this = Rc::new(Self {
+                stack: stack.clone(),
+                alias: alias.map(str::to_string),
+            });
+            stack.add_provider(this.clone());
+            this
+        }
+    }
+
+    impl Scope for TestProvider {
+        fn stack(&self) -> Stack {
+            self.stack.clone()
+        }
+
+        fn path(&self) -> Path {
+            let mut path = self.stack.path();
+            path.push("registry.terraform.io/hashicorp/kubernetes");
+            path
+        }
+    }
+
+    impl Provider for TestProvider {
+        fn to_schema(&self) -> (String, tf_bindgen_schema::document::Provider) {
+            ("2.19.0".to_string(), Default::default())
+        }
+
+        fn alias(&self) -> Option<&str> {
+            self.alias.as_deref()
+        }
+    }
+
+    struct TestDataSource {
+        stack: Stack,
+        name: String,
+    }
+
+    impl TestDataSource {
+        fn create(stack: &Stack, name: &str) -> Rc<Self> {
+            let this = Rc::new(Self {
+                stack: stack.clone(),
+                name: name.to_string(),
+            });
+            stack.add_data_source(this.clone());
+            this
+        }
+    }
+
+    impl Scope for TestDataSource {
+        fn stack(&self) -> Stack {
+            self.stack.clone()
+        }
+
+        fn path(&self) -> Path {
+            let mut path = self.stack.path();
+            path.push(&self.name);
+            path
+        }
+    }
+
+    impl L1Construct for TestDataSource {
+        fn to_schema(&self) -> (String, Resource) {
+            let path = self.path();
+            let metadata = ResourceMetadata {
+                path: path.to_string(),
+                unique_id: path.name().to_string(),
+                blocks: None,
+            };
+            let resource = Resource {
+                meta: ResourceMeta { metadata },
+                config: Default::default(),
+            };
+            ("kubernetes_namespace".to_string(), resource)
+        }
+
+        fn reference(&self) -> String {
+            format!("data.kubernetes_namespace.{}", self.path().id())
+        }
+    }
+
+    #[test]
+    fn data_source() {
+        let stack = Stack::new("test");
+        let namespace = TestDataSource::create(&stack, "default");
+        let id = namespace.path().id();
+        assert_eq!(
+            namespace.reference(),
+            format!("data.kubernetes_namespace.{id}")
+        );
+        let document = stack.to_document();
+        assert!(document.resource.is_empty());
+        let data_source = &document.data["kubernetes_namespace"][&id];
+        assert_eq!(data_source.meta.metadata.unique_id, "default");
+    }
+
+    #[test]
+    fn provider_alias() {
+        let stack = Stack::new("test");
+        TestProvider::create(&stack, None);
+        let west = TestProvider::create(&stack, Some("west"));
+        assert_eq!(west.reference(), "kubernetes.west");
+        let document = stack.to_document();
+        let provider = &document.provider["kubernetes"];
+        assert_eq!(provider.len(), 2);
+        assert_eq!(provider[1]["alias"], "west");
+    }
+
+    #[test]
+    #[should_panic]
+    fn provider_without_alias() {
+        let stack = Stack::new("test");
+        TestProvider::create(&stack, None);
+        TestProvider::create(&stack, None);
+        stack.to_document();
+    }
+
+    #[test]
+    fn backend_config() {
+        let stack = Stack::new("test");
+        stack.backend(GcsBackend {
+            bucket: "terraform-state".to_string(),
+            prefix: Some("test".to_string()),
+            ..Default::default()
+        });
+        let document = stack.to_document();
+        assert_eq!(document.meta.metadata.backend, "gcs");
+        let expected = serde_json::json!({ "bucket": "terraform-state", "prefix": "test" });
+        assert_eq!(document.terraform.backend["gcs"], expected);
+    }
+
+    #[test]
+    fn local_reference() {
+        let stack = Stack::new("test");
+        let prefix = stack.local("prefix", "nginx");
+        assert_eq!(*prefix, "nginx");
+        let prefix = serde_json::to_value(&prefix).unwrap();
+        assert_eq!(prefix, "${local.prefix}");
+        let document = stack.to_document();
+        assert_eq!(document.locals["prefix"], "nginx");
+    }
+
+    #[test]
+    fn local_of_reference() {
+        let stack = Stack::new("test");
+        let namespace = stack.variable::<String>("namespace").build();
+        stack.local("namespace", &namespace);
+        let document = stack.to_document();
+        assert_eq!(document.locals["namespace"], "${var.namespace}");
+    }
+
+    #[test]
+    fn local_escape_literal() {
+        let stack = Stack::new("test");
+        stack.local("script", "echo ${HOME}");
+        stack.local("raw", Value::<String>::raw_expression("${var.name}"));
+        let document = stack.to_document();
+        assert_eq!(document.locals["script"], "echo $${HOME}");
+        assert_eq!(document.locals["raw"], "${var.name}");
+    }
+
+    #[test]
+    fn stable_document() {
+        let synth = || {
+            let stack = Stack::new("test");
+            TestProvider::create(&stack, None);
+            for name in ["zone", "region", "project"] {
+                stack.local(name, name);
+                stack.add_output(name, name).build();
+            }
+            serde_json::to_string(&stack.to_document()).unwrap()
+        };
+        let document = synth();
+        assert_eq!(document, synth());
+        let project = document.find(r#""project":"project""#).unwrap();
+        let zone = document.find(r#""zone":"zone""#).unwrap();
+        assert!(project < zone);
+    }
+}
@@ -3,11 +3,13 @@ use std::ops::{Deref, Index};
 use serde::{Serialize, Serializer};
 
 use super::prepare::Prepare;
+use crate::reference;
 
 /// Used to store field information and value.
 #[derive(Clone)]
 pub struct Cell<T> {
     path: String,
+    owner: Option<String>,
     value: T,
 }
 
@@ -16,6 +18,7 @@ impl<T> Cell<T> {
     pub fn new(name: impl Into<String>, value: impl Into<T>) -> Self {
         Self {
             path: name.into(),
+            owner: None,
             value: value.into(),
         }
     }
@@ -24,6 +27,12 @@ impl<T> Cell<T> {
         &self.path
     }
 
+    /// Returns the name of the stack declaring this field. Set when preparing the fields of
+    /// a resource or data source (see [`crate::Stack::attribute_prefix`]).
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
     pub fn value(&self) -> &T {
         &self.value
     }
@@ -42,9 +51,10 @@ impl<T> Index<usize> for Cell<Option<Vec<T>>> {
 
 impl<T: Prepare + Clone> Prepare for Cell<T> {
     fn prepare(self, prefix: impl Into<String>) -> Self {
-        let path = prefix.into();
-        let value = self.value.prepare(&path);
-        Self { path, value }
+        let prefix = prefix.into();
+        let value = self.value.prepare(&prefix);
+        let (owner, path) = reference::unmark(prefix);
+        Self { path, owner, value }
     }
 }
 
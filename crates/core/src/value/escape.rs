@@ -0,0 +1,301 @@
+//! Used to escape template sequences (i.e. `${` and `%{`) in literal values. Serializing a
+//! literal [`Value`](super::Value) will wrap the serializer with [`EscapeSerializer`], which
+//! escapes every string passed to it. References and templates are marked using a newtype
+//! struct named [`TOKEN`], which will be passed through unchanged.
+
+use serde::ser::{
+    SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::{Serialize, Serializer};
+
+/// Name of the newtype struct used to mark already processed values.
+pub(crate) const TOKEN: &str = "$tf_bindgen::Value";
+
+/// Escape template sequences (i.e. `${` and `%{`) in `text`.
+pub(crate) fn escape(text: &str) -> String {
+    text.replace("${", "$${").replace("%{", "%%{")
+}
+
+/// Used to serialize `T` with all strings escaped.
+pub(crate) struct Escape<'a, T: ?Sized>(pub &'a T);
+
+pub(crate) struct EscapeSerializer<S>(S);
+
+pub(crate) struct EscapeCompound<C>(C);
+
+impl<T: Serialize + ?Sized> Serialize for Escape<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(EscapeSerializer(serializer))
+    }
+}
+
+macro_rules! forward {
+    ($($method:ident($t:ty)),*$(,)?) => {
+        $(
+            fn $method(self, v: $t) -> Result<Self::Ok, Self::Error> {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for EscapeSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = EscapeCompound<S::SerializeSeq>;
+    type SerializeTuple = EscapeCompound<S::SerializeTuple>;
+    type SerializeTupleStruct = EscapeCompound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = EscapeCompound<S::SerializeTupleVariant>;
+    type SerializeMap = EscapeCompound<S::SerializeMap>;
+    type SerializeStruct = EscapeCompound<S::SerializeStruct>;
+    type SerializeStructVariant = EscapeCompound<S::SerializeStructVariant>;
+
+    forward! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.0.serialize_str(&escape(v))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.0.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        self.0.serialize_some(&Escape(value))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.0.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.0.serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        if name == TOKEN {
+            self.0.serialize_newtype_struct(name, value)
+        } else {
+            self.0.serialize_newtype_struct(name, &Escape(value))
+        }
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.0
+            .serialize_newtype_variant(name, variant_index, variant, &Escape(value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.0.serialize_seq(len).map(EscapeCompound)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.0.serialize_tuple(len).map(EscapeCompound)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.0.serialize_tuple_struct(name, len).map(EscapeCompound)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.0
+            .serialize_tuple_variant(name, variant_index, variant, len)
+            .map(EscapeCompound)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.0.serialize_map(len).map(EscapeCompound)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.0.serialize_struct(name, len).map(EscapeCompound)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.0
+            .serialize_struct_variant(name, variant_index, variant, len)
+            .map(EscapeCompound)
+    }
+}
+
+macro_rules! impl_compound {
+    ($trait:ident, field) => {
+        impl<C: $trait> $trait for EscapeCompound<C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn serialize_field<T: Serialize + ?Sized>(
+                &mut self,
+                key: &'static str,
+                value: &T,
+            ) -> Result<(), Self::Error> {
+                self.0.serialize_field(key, &Escape(value))
+            }
+
+            fn end(self) -> Result<Self::Ok, Self::Error> {
+                self.0.end()
+            }
+        }
+    };
+    ($trait:ident, $method:ident) => {
+        impl<C: $trait> $trait for EscapeCompound<C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+                self.0.$method(&Escape(value))
+            }
+
+            fn end(self) -> Result<Self::Ok, Self::Error> {
+                self.0.end()
+            }
+        }
+    };
+}
+
+impl_compound!(SerializeSeq, serialize_element);
+impl_compound!(SerializeTuple, serialize_element);
+impl_compound!(SerializeTupleStruct, serialize_field);
+impl_compound!(SerializeTupleVariant, serialize_field);
+impl_compound!(SerializeStruct, field);
+impl_compound!(SerializeStructVariant, field);
+
+impl<C: SerializeMap> SerializeMap for EscapeCompound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.0.serialize_key(&Escape(key))
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.0.serialize_value(&Escape(value))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.0.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use serde::Serialize;
+
+    use crate::value::{Cell, IntoValue};
+    use crate::Value;
+
+    #[derive(Serialize)]
+    struct Metadata {
+        name: Cell<Value<String>>,
+        labels: Value<HashMap<String, Value<String>>>,
+        args: Vec<Value<String>>,
+    }
+
+    #[test]
+    fn escape_literal() {
+        let value = "echo ${HOME} %{if}".into_value();
+        let value = serde_json::to_value(value).unwrap();
+        assert_eq!(value, "echo $${HOME} %%{if}");
+    }
+
+    #[test]
+    fn escape_nested() {
+        let labels = HashMap::from([
+            ("app".to_string(), "${app}".into_value()),
+            ("name".to_string(), Value::raw_expression("${var.name}")),
+        ]);
+        let name: Cell<Value<String>> = Cell::new("var.name", "${name}".into_value());
+        let metadata = Metadata {
+            name,
+            labels: Value::Value {
+                value: Rc::new(labels),
+            },
+            args: vec!["${arg}".into_value(), Value::raw_expression("${var.arg}")],
+        };
+        let value = Value::Value {
+            value: Rc::new(metadata),
+        };
+        let value = serde_json::to_value(value).unwrap();
+        let expected = serde_json::json!({
+            "name": "$${name}",
+            "labels": { "app": "$${app}", "name": "${var.name}" },
+            "args": ["$${arg}", "${var.arg}"]
+        });
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn escape_json_object() {
+        let value = serde_json::json!({ "script": ["${x}", { "${key}": "%{y}" }] });
+        let value = serde_json::to_value(value.into_value()).unwrap();
+        let expected = serde_json::json!({ "script": ["$${x}", { "$${key}": "%%{y}" }] });
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn skip_reference() {
+        let name: Cell<Value<String>> = Cell::new("kubernetes_pod.nginx.id", "${x}".into_value());
+        let value = (&name).into_value();
+        let value = serde_json::to_value(value).unwrap();
+        assert_eq!(value, "${kubernetes_pod.nginx.id}");
+    }
+}
@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::ops::{Add, BitAnd, BitOr, Div, Mul, Neg, Not, Rem, Sub};
+
+use serde::Serialize;
+
+use super::escape::escape;
+use super::{Cell, Computed, IntoValue, Value};
+use crate::reference;
+
+/// Used to convert values and references into Terraform expressions (e.g. `var.name` or
+/// `"nginx"`). In contrast to the serialized value, expressions are not wrapped in `${...}`.
+///
+/// References to objects of a stack are marked with the stack declaring them, so they can be
+/// resolved once the expression is used by a stack (see [`crate::App`]). Thus, expressions
+/// should only be passed to `tf_bindgen` (e.g. using [`Value::raw_expression`]).
+pub trait ToExpression {
+    fn to_expression(&self) -> String;
+
+    /// Returns this expression as part of a string template (e.g. `${var.name}`).
+    fn to_template(&self) -> String {
+        format!("${{{}}}", self.to_expression())
+    }
+}
+
+/// Used to implement `tf_bindgen::interpolate!`. Will replace every placeholder (i.e. `{}` or
+/// `{<index>}`) in `format` with the corresponding argument.
+///
+/// # Panics
+///
+/// Will panic if `format` is invalid or references a missing argument.
+pub fn interpolate(format: &str, args: &[&dyn ToExpression]) -> Value<String> {
+    let mut template = String::new();
+    let mut literal = String::new();
+    let mut next = 0;
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                literal.push(c);
+            }
+            ('{', _) => {
+                let mut index = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => index.push(c),
+                        None => panic!("unterminated placeholder in '{format}'"),
+                    }
+                }
+                let index = if index.is_empty() {
+                    next += 1;
+                    next - 1
+                } else {
+                    index
+                        .parse()
+                        .unwrap_or_else(|_| panic!("invalid placeholder '{{{index}}}'"))
+                };
+                let arg = args
+                    .get(index)
+                    .unwrap_or_else(|| panic!("missing argument {index} of '{format}'"));
+                template += &escape(&literal);
+                template += &arg.to_template();
+                literal.clear();
+            }
+            ('}', _) => panic!("unmatched '}}' in '{format}'"),
+            (c, _) => literal.push(c),
+        }
+    }
+    template += &escape(&literal);
+    Value::Template { template }
+}
+
+impl<T: Serialize> Value<T> {
+    /// Create a new value from the Terraform expression `expression` (e.g. `var.name`).
+    pub(crate) fn from_expression(expression: impl Into<String>) -> Self {
+        Value::Ref {
+            path: expression.into(),
+            value: None,
+            owner: None,
+        }
+    }
+
+    /// Returns `true` if the result of the expression `self == other`.
+    pub fn equal(&self, other: impl IntoValue<T>) -> Value<bool> {
+        binary_expression(self, "==", &other.into_value())
+    }
+
+    /// Returns `true` if the result of the expression `self != other`.
+    pub fn not_equal(&self, other: impl IntoValue<T>) -> Value<bool> {
+        binary_expression(self, "!=", &other.into_value())
+    }
+}
+
+impl Value<i64> {
+    pub fn less_than(&self, other: impl IntoValue<i64>) -> Value<bool> {
+        compare(self, "<", &other.into_value(), |a, b| a < b)
+    }
+
+    pub fn less_equal(&self, other: impl IntoValue<i64>) -> Value<bool> {
+        compare(self, "<=", &other.into_value(), |a, b| a <= b)
+    }
+
+    pub fn greater_than(&self, other: impl IntoValue<i64>) -> Value<bool> {
+        compare(self, ">", &other.into_value(), |a, b| a > b)
+    }
+
+    pub fn greater_equal(&self, other: impl IntoValue<i64>) -> Value<bool> {
+        compare(self, ">=", &other.into_value(), |a, b| a >= b)
+    }
+}
+
+fn binary_expression<T: Serialize, U: Serialize, R: Serialize>(
+    lhs: &Value<T>,
+    op: &str,
+    rhs: &Value<U>,
+) -> Value<R> {
+    let lhs = lhs.to_expression();
+    let rhs = rhs.to_expression();
+    Value::from_expression(format!("({lhs} {op} {rhs})"))
+}
+
+fn compare(
+    lhs: &Value<i64>,
+    op: &str,
+    rhs: &Value<i64>,
+    f: impl Fn(i64, i64) -> bool,
+) -> Value<bool> {
+    match (lhs, rhs) {
+        (Value::Value { value: lhs }, Value::Value { value: rhs }) => f(**lhs, **rhs).into_value(),
+        _ => binary_expression(lhs, op, rhs),
+    }
+}
+
+/// Implement the operator `$trait` for `Value<$t>`. Literals are evaluated using `$f`. If `$f`
+/// returns `None` (e.g. on overflow), the expression will be passed to Terraform instead.
+macro_rules! impl_operator {
+    ($trait:ident, $method:ident, $t:ty, $op:literal, $f:expr) => {
+        impl<R: IntoValue<$t>> $trait<R> for Value<$t> {
+            type Output = Value<$t>;
+
+            fn $method(self, rhs: R) -> Self::Output {
+                let rhs = rhs.into_value();
+                match (&self, &rhs) {
+                    (Value::Value { value: a }, Value::Value { value: b }) => {
+                        let f: fn($t, $t) -> Option<$t> = $f;
+                        match f((**a).clone(), (**b).clone()) {
+                            Some(result) => result.into_value(),
+                            None => binary_expression(&self, $op, &rhs),
+                        }
+                    }
+                    _ => binary_expression(&self, $op, &rhs),
+                }
+            }
+        }
+    };
+}
+
+impl_operator!(Add, add, i64, "+", i64::checked_add);
+impl_operator!(Sub, sub, i64, "-", i64::checked_sub);
+impl_operator!(Mul, mul, i64, "*", i64::checked_mul);
+impl_operator!(Rem, rem, i64, "%", i64::checked_rem);
+impl_operator!(BitAnd, bitand, bool, "&&", |a, b| Some(a && b));
+impl_operator!(BitOr, bitor, bool, "||", |a, b| Some(a || b));
+
+/// Terraform will divide numbers without truncation. So we will round down the result to keep
+/// the result an integer. Divisions by zero are passed to Terraform as is.
+impl<R: IntoValue<i64>> Div<R> for Value<i64> {
+    type Output = Value<i64>;
+
+    fn div(self, rhs: R) -> Self::Output {
+        let rhs = rhs.into_value();
+        match (&self, &rhs) {
+            (Value::Value { value: lhs }, Value::Value { value: rhs })
+                if lhs.checked_div(**rhs).is_some() =>
+            {
+                let (lhs, rhs) = (**lhs, **rhs);
+                let result = lhs / rhs;
+                if lhs % rhs != 0 && (lhs < 0) != (rhs < 0) {
+                    (result - 1).into_value()
+                } else {
+                    result.into_value()
+                }
+            }
+            _ => {
+                let lhs = self.to_expression();
+                let rhs = rhs.to_expression();
+                Value::from_expression(format!("floor({lhs} / {rhs})"))
+            }
+        }
+    }
+}
+
+impl Neg for Value<i64> {
+    type Output = Value<i64>;
+
+    fn neg(self) -> Self::Output {
+        match &self {
+            Value::Value { value } if value.checked_neg().is_some() => (-**value).into_value(),
+            _ => Value::from_expression(format!("(-{})", self.to_expression())),
+        }
+    }
+}
+
+impl Not for Value<bool> {
+    type Output = Value<bool>;
+
+    fn not(self) -> Self::Output {
+        match &self {
+            Value::Value { value } => (!**value).into_value(),
+            _ => Value::from_expression(format!("(!{})", self.to_expression())),
+        }
+    }
+}
+
+impl<T: Serialize> ToExpression for Value<T> {
+    fn to_expression(&self) -> String {
+        match self {
+            Value::Ref { path, owner, .. } => owned_path(owner.as_deref(), path),
+            Value::Value { .. } => {
+                let value = reference::with_expression(|| serde_json::to_value(self))
+                    .expect("valid literal value");
+                to_literal(&value)
+            }
+            Value::Template { template } => quote(template),
+        }
+    }
+
+    fn to_template(&self) -> String {
+        match self {
+            Value::Value { .. } => match reference::with_expression(|| serde_json::to_value(self))
+                .expect("valid literal value")
+            {
+                serde_json::Value::String(value) => value,
+                serde_json::Value::Number(value) => value.to_string(),
+                serde_json::Value::Bool(value) => value.to_string(),
+                value => format!("${{{}}}", to_literal(&value)),
+            },
+            Value::Template { template } => template.clone(),
+            Value::Ref { path, owner, .. } => {
+                format!("${{{}}}", owned_path(owner.as_deref(), path))
+            }
+        }
+    }
+}
+
+/// Returns `path` marked with its owner (see [`crate::reference::mark`]).
+fn owned_path(owner: Option<&str>, path: &str) -> String {
+    match owner {
+        Some(owner) => reference::mark(owner, path),
+        None => path.to_string(),
+    }
+}
+
+impl<T: Serialize> ToExpression for Cell<Value<T>> {
+    fn to_expression(&self) -> String {
+        owned_path(self.owner(), self.path())
+    }
+}
+
+impl<T: Serialize> ToExpression for Cell<Option<Value<T>>> {
+    fn to_expression(&self) -> String {
+        owned_path(self.owner(), self.path())
+    }
+}
+
+impl<T: Serialize> ToExpression for Cell<Computed<Value<T>>> {
+    fn to_expression(&self) -> String {
+        owned_path(self.owner(), self.path())
+    }
+}
+
+impl<T: ToExpression + ?Sized> ToExpression for &T {
+    fn to_expression(&self) -> String {
+        (**self).to_expression()
+    }
+
+    fn to_template(&self) -> String {
+        (**self).to_template()
+    }
+}
+
+impl<T: ToExpression> ToExpression for [T] {
+    fn to_expression(&self) -> String {
+        let elements: Vec<_> = self.iter().map(ToExpression::to_expression).collect();
+        format!("[{}]", elements.join(", "))
+    }
+}
+
+impl<T: ToExpression, const S: usize> ToExpression for [T; S] {
+    fn to_expression(&self) -> String {
+        self[..].to_expression()
+    }
+}
+
+impl<T: ToExpression> ToExpression for Vec<T> {
+    fn to_expression(&self) -> String {
+        self[..].to_expression()
+    }
+}
+
+impl<T: ToExpression> ToExpression for HashMap<String, T> {
+    fn to_expression(&self) -> String {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+        let entries: Vec<_> = entries
+            .into_iter()
+            .map(|(key, value)| format!("{} = {}", quote(key), value.to_expression()))
+            .collect();
+        format!("{{{}}}", entries.join(", "))
+    }
+}
+
+impl ToExpression for str {
+    fn to_expression(&self) -> String {
+        quote(&escape(self))
+    }
+
+    fn to_template(&self) -> String {
+        escape(self)
+    }
+}
+
+impl ToExpression for String {
+    fn to_expression(&self) -> String {
+        quote(&escape(self))
+    }
+
+    fn to_template(&self) -> String {
+        escape(self)
+    }
+}
+
+impl ToExpression for i64 {
+    fn to_expression(&self) -> String {
+        self.to_string()
+    }
+
+    fn to_template(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ToExpression for bool {
+    fn to_expression(&self) -> String {
+        self.to_string()
+    }
+
+    fn to_template(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Convert a JSON value into an equivalent Terraform literal.
+fn to_literal(value: &serde_json::Value) -> String {
+    use serde_json::Value::*;
+    match value {
+        Null => "null".to_string(),
+        Bool(value) => value.to_string(),
+        Number(value) => value.to_string(),
+        String(value) => quote(value),
+        Array(values) => {
+            let values: Vec<_> = values.iter().map(to_literal).collect();
+            format!("[{}]", values.join(", "))
+        }
+        Object(entries) => {
+            let entries: Vec<_> = entries
+                .iter()
+                .map(|(key, value)| format!("{} = {}", quote(key), to_literal(value)))
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+    }
+}
+
+/// Convert `template` into a quoted Terraform string. Template sequences will not be escaped.
+fn quote(template: &str) -> String {
+    let mut result = String::with_capacity(template.len() + 2);
+    result.push('"');
+    for c in template.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            // Marks of references must be kept to resolve them later on.
+            c if reference::is_mark(c) => result.push(c),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::{interpolate, Cell, IntoValue, ToExpression};
+    use crate::Value;
+
+    fn var<T: serde::Serialize>(name: &str) -> Value<T> {
+        Value::from_expression(format!("var.{name}"))
+    }
+
+    #[test]
+    fn literal_expression() {
+        assert_eq!("nginx".into_value().to_expression(), r#""nginx""#);
+        assert_eq!("a\"b\n".to_expression(), r#""a\"b\n""#);
+        assert_eq!("${a}".to_expression(), r#""$${a}""#);
+        let list = vec!["a".into_value(), var("b")];
+        assert_eq!(list.to_expression(), r#"["a", var.b]"#);
+    }
+
+    #[test]
+    fn interpolate_template() {
+        let name = var::<String>("name");
+        let value = interpolate("{}-{{{}}}-{0}: ${{x}}", &[&name, &3]);
+        let value = serde_json::to_value(value).unwrap();
+        assert_eq!(value, "${var.name}-{3}-${var.name}: $${x}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn interpolate_missing_argument() {
+        interpolate("{}-{}", &[&"nginx"]);
+    }
+
+    #[test]
+    fn cell_expression() {
+        let cell: Cell<Value<String>> = Cell::new("kubernetes_pod.nginx.id", "id".into_value());
+        assert_eq!(cell.to_expression(), "kubernetes_pod.nginx.id");
+    }
+
+    #[test]
+    fn arithmetic_literal() {
+        let value = 7.into_value() / 2 + 1;
+        assert_eq!(*value, 4);
+        let value = (-7).into_value() / 2;
+        assert_eq!(*value, -4);
+    }
+
+    #[test]
+    fn arithmetic_literal_overflow() {
+        let value = serde_json::to_value(1.into_value() / 0).unwrap();
+        assert_eq!(value, "${floor(1 / 0)}");
+        let value = serde_json::to_value(1.into_value() % 0).unwrap();
+        assert_eq!(value, "${(1 % 0)}");
+        let value = serde_json::to_value(i64::MAX.into_value() + 1).unwrap();
+        assert_eq!(value, "${(9223372036854775807 + 1)}");
+    }
+
+    #[test]
+    fn arithmetic_reference() {
+        let value = var::<i64>("replicas") * 2 + 1;
+        let value = serde_json::to_value(value).unwrap();
+        assert_eq!(value, "${((var.replicas * 2) + 1)}");
+        let value = var::<i64>("replicas") / 2;
+        let value = serde_json::to_value(value).unwrap();
+        assert_eq!(value, "${floor(var.replicas / 2)}");
+    }
+
+    #[test]
+    fn comparison() {
+        let value = var::<i64>("replicas").greater_than(0) & !var::<bool>("disabled");
+        let value = serde_json::to_value(value).unwrap();
+        assert_eq!(value, "${((var.replicas > 0) && (!var.disabled))}");
+        assert!(*3.into_value().less_than(4));
+    }
+}
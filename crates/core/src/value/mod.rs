@@ -5,23 +5,38 @@ use std::rc::Rc;
 
 use serde::{Serialize, Serializer};
 
+use crate::reference;
+
 mod cell;
+mod escape;
+mod expression;
 mod prelude;
 mod prepare;
+mod set;
 
 pub use cell::Cell;
+pub use expression::{interpolate, ToExpression};
 pub use prelude::*;
 pub use prepare::Prepare;
+pub use set::ValueSet;
 
 #[derive(Clone, PartialEq, Eq)]
 pub enum Value<T> {
     Ref {
         path: String,
         value: Option<Box<Value<T>>>,
+        /// Name of the stack declaring the referenced object. Used to resolve references to
+        /// other stacks of the same app (see [`crate::App`]).
+        owner: Option<String>,
     },
     Value {
         value: Rc<T>,
     },
+    /// A Terraform string template (e.g. `nginx-${var.name}`), which will be passed to Terraform
+    /// as is. See `tf_bindgen::interpolate!` and [`Value::raw_expression`].
+    Template {
+        template: String,
+    },
 }
 
 pub struct Computed<T> {
@@ -29,12 +44,22 @@ pub struct Computed<T> {
 }
 
 impl<T> Value<T> {
+    /// Create a value passed to Terraform as is, i.e. template sequences (e.g. `${var.name}`)
+    /// will not be escaped. Use this to write expressions not covered by
+    /// [`crate::function`] or `tf_bindgen::interpolate!`.
+    pub fn raw_expression(expression: impl Into<String>) -> Self {
+        Value::Template {
+            template: expression.into(),
+        }
+    }
+
     pub fn get(&self) -> Rc<T> {
         match &self {
             Value::Ref {
                 value: Some(value), ..
             } => value.get(),
             Value::Value { value } => value.clone(),
+            Value::Template { .. } => unimplemented!("can not evaluate templates"),
             _ => unimplemented!("can not unknown referenced values"),
         }
     }
@@ -49,6 +74,7 @@ impl<T> Deref for Value<T> {
                 value: Some(value), ..
             } => value,
             Value::Value { value } => value,
+            Value::Template { .. } => unimplemented!("can not dereference templates"),
             _ => unimplemented!("can not dereference computed values"),
         }
     }
@@ -63,6 +89,7 @@ impl<T: Hash> Hash for Value<T> {
                 state.write(b"}");
             }
             Value::Value { value } => value.hash(state),
+            Value::Template { template } => template.hash(state),
         }
     }
 }
@@ -91,8 +118,17 @@ impl<T: Serialize> Serialize for Value<T> {
         S: Serializer,
     {
         match self {
-            Value::Ref { path, .. } => format!("${{{path}}}").serialize(serializer),
-            Value::Value { value } => value.serialize(serializer),
+            Value::Ref { path, owner, .. } => {
+                let path = reference::resolve(owner.as_deref(), path);
+                serializer.serialize_newtype_struct(escape::TOKEN, &format!("${{{path}}}"))
+            }
+            Value::Value { value } => {
+                serializer.serialize_newtype_struct(escape::TOKEN, &escape::Escape(value.as_ref()))
+            }
+            Value::Template { template } => {
+                let template = reference::resolve_marks(template);
+                serializer.serialize_newtype_struct(escape::TOKEN, &template)
+            }
         }
     }
 }
@@ -100,7 +136,7 @@ impl<T: Serialize> Serialize for Value<T> {
 impl<T: Prepare + Clone> Prepare for Value<T> {
     fn prepare(self, prefix: impl Into<String>) -> Self {
         match self {
-            Value::Ref { .. } => self,
+            Value::Ref { .. } | Value::Template { .. } => self,
             Value::Value { value } => Self::Value {
                 value: value.prepare(prefix),
             },
@@ -2,8 +2,8 @@ use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::rc::Rc;
 
-use super::Cell;
 pub use super::Value;
+use super::{Cell, Computed, ValueSet};
 
 pub trait IntoValue<T> {
     fn into_value(self) -> Value<T>;
@@ -14,7 +14,7 @@ pub trait IntoValueList<T> {
 }
 
 pub trait IntoValueSet<T> {
-    fn into_value_set(self) -> HashSet<Value<T>>;
+    fn into_value_set(self) -> ValueSet<T>;
 }
 
 pub trait IntoValueMap<T> {
@@ -63,11 +63,20 @@ impl<T: Clone> IntoValue<T> for &Value<T> {
     }
 }
 
+impl<T> IntoValue<ValueSet<T>> for ValueSet<T> {
+    fn into_value(self) -> Value<ValueSet<T>> {
+        Value::Value {
+            value: Rc::new(self),
+        }
+    }
+}
+
 impl<T: Clone> IntoValue<T> for &Cell<Value<T>> {
     fn into_value(self) -> Value<T> {
         Value::Ref {
             path: self.path().to_string(),
             value: Some(Box::new(self.value().clone())),
+            owner: self.owner().map(str::to_string),
         }
     }
 }
@@ -77,6 +86,17 @@ impl<T: Clone> IntoValue<T> for &Cell<Option<Value<T>>> {
         Value::Ref {
             path: self.path().to_string(),
             value: self.value().as_ref().cloned().map(Box::new),
+            owner: self.owner().map(str::to_string),
+        }
+    }
+}
+
+impl<T> IntoValue<T> for &Cell<Computed<Value<T>>> {
+    fn into_value(self) -> Value<T> {
+        Value::Ref {
+            path: self.path().to_string(),
+            value: None,
+            owner: self.owner().map(str::to_string),
         }
     }
 }
@@ -108,22 +128,28 @@ where
     }
 }
 
-impl<T, U> IntoValueSet<T> for HashSet<U>
+impl<T, U, S> IntoValueSet<T> for HashSet<U, S>
 where
     T: Hash + Eq,
     U: IntoValue<T>,
 {
-    fn into_value_set(self) -> HashSet<Value<T>> {
+    fn into_value_set(self) -> ValueSet<T> {
         self.into_iter().map(IntoValue::into_value).collect()
     }
 }
 
+impl<T> IntoValueSet<T> for ValueSet<T> {
+    fn into_value_set(self) -> ValueSet<T> {
+        self
+    }
+}
+
 impl<T, U, const S: usize> IntoValueSet<T> for [U; S]
 where
     T: Hash + Eq,
     U: IntoValue<T>,
 {
-    fn into_value_set(self) -> HashSet<Value<T>> {
+    fn into_value_set(self) -> ValueSet<T> {
         self.into_iter().map(IntoValue::into_value).collect()
     }
 }
@@ -133,7 +159,7 @@ where
     T: Hash + Eq,
     U: IntoValue<T> + Clone,
 {
-    fn into_value_set(self) -> HashSet<Value<T>> {
+    fn into_value_set(self) -> ValueSet<T> {
         self.iter().cloned().map(IntoValue::into_value).collect()
     }
 }
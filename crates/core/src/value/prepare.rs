@@ -42,7 +42,7 @@ impl<T: Prepare> Prepare for Vec<T> {
     }
 }
 
-impl<T> Prepare for HashSet<T> {
+impl<T, S> Prepare for HashSet<T, S> {
     fn prepare(self, _: impl Into<String>) -> Self {
         self
     }
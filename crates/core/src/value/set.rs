@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+use super::prepare::Prepare;
+use super::Value;
+
+/// Set of values. Elements are serialized ordered by their JSON representation, so identical
+/// sets will always result in the same output.
+#[derive(Clone)]
+pub struct ValueSet<T> {
+    values: HashSet<Value<T>>,
+}
+
+impl<T> Default for ValueSet<T> {
+    fn default() -> Self {
+        Self {
+            values: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq> PartialEq for ValueSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<T: Hash + Eq> Eq for ValueSet<T> {}
+
+impl<T> Deref for ValueSet<T> {
+    type Target = HashSet<Value<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl<T> DerefMut for ValueSet<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.values
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<Value<T>> for ValueSet<T> {
+    fn from_iter<I: IntoIterator<Item = Value<T>>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> IntoIterator for ValueSet<T> {
+    type Item = Value<T>;
+    type IntoIter = std::collections::hash_set::IntoIter<Value<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ValueSet<T> {
+    type Item = &'a Value<T>;
+    type IntoIter = std::collections::hash_set::Iter<'a, Value<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<T> Prepare for ValueSet<T> {
+    fn prepare(self, _: impl Into<String>) -> Self {
+        self
+    }
+}
+
+impl<T: Serialize> Serialize for ValueSet<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut values = self
+            .values
+            .iter()
+            .map(|value| Ok((serde_json::to_string(value)?, value)))
+            .collect::<Result<Vec<_>, serde_json::Error>>()
+            .map_err(S::Error::custom)?;
+        values.sort_by(|(a, _), (b, _)| a.cmp(b));
+        serializer.collect_seq(values.into_iter().map(|(_, value)| value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValueSet;
+    use crate::value::IntoValue;
+
+    #[test]
+    fn serialize_ordered() {
+        let values = ["b", "c", "a", "d"];
+        let forward: ValueSet<String> = values.iter().map(|v| v.into_value()).collect();
+        let backward: ValueSet<String> = values.iter().rev().map(|v| v.into_value()).collect();
+        assert_eq!(
+            serde_json::to_string(&forward).unwrap(),
+            serde_json::to_string(&backward).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_string(&forward).unwrap(),
+            r#"["a","b","c","d"]"#
+        );
+    }
+}
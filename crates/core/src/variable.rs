@@ -0,0 +1,229 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use tf_bindgen_schema::document;
+
+use crate::reference;
+use crate::value::ValueSet;
+use crate::{Stack, Value};
+
+/// Used to map Rust types to Terraform type constraints.
+pub trait VariableType {
+    /// Returns the type constraint used by Terraform (e.g. `string`).
+    fn type_constraint() -> String;
+}
+
+macro_rules! impl_variable_type {
+    ($t:ty, $constraint:literal) => {
+        impl VariableType for $t {
+            fn type_constraint() -> String {
+                $constraint.to_string()
+            }
+        }
+    };
+}
+
+impl_variable_type!(String, "string");
+impl_variable_type!(bool, "bool");
+impl_variable_type!(i64, "number");
+impl_variable_type!(f64, "number");
+impl_variable_type!(serde_json::Value, "any");
+
+impl<T: VariableType> VariableType for Value<T> {
+    fn type_constraint() -> String {
+        T::type_constraint()
+    }
+}
+
+impl<T: VariableType> VariableType for Vec<T> {
+    fn type_constraint() -> String {
+        format!("list({})", T::type_constraint())
+    }
+}
+
+impl<T: VariableType, S> VariableType for HashSet<T, S> {
+    fn type_constraint() -> String {
+        format!("set({})", T::type_constraint())
+    }
+}
+
+impl<T: VariableType> VariableType for ValueSet<T> {
+    fn type_constraint() -> String {
+        format!("set({})", T::type_constraint())
+    }
+}
+
+impl<T: VariableType, S> VariableType for HashMap<String, T, S> {
+    fn type_constraint() -> String {
+        format!("map({})", T::type_constraint())
+    }
+}
+
+impl<T: VariableType> VariableType for BTreeMap<String, T> {
+    fn type_constraint() -> String {
+        format!("map({})", T::type_constraint())
+    }
+}
+
+/// Used to configure a Terraform input variable of a stack. See [`Stack::variable`].
+pub struct VariableBuilder<T> {
+    stack: Stack,
+    name: String,
+    variable: document::Variable,
+    _p: PhantomData<T>,
+}
+
+impl<T: VariableType + Serialize> VariableBuilder<T> {
+    /// Overwrite the type constraint derived from `T` (e.g. to use `list(string)` together
+    /// with [`serde_json::Value`]).
+    pub fn r#type(&mut self, constraint: impl Into<String>) -> &mut Self {
+        self.variable.ty = Some(constraint.into());
+        self
+    }
+
+    /// Set the default value of this variable. The variable will be optional afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `value` cannot be serialized to JSON.
+    pub fn default(&mut self, value: impl Into<T>) -> &mut Self {
+        let value = serde_json::to_value(value.into()).expect("valid default value");
+        self.variable.default = Some(value);
+        self
+    }
+
+    /// Set the description of this variable.
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.variable.description = Some(description.into());
+        self
+    }
+
+    /// Mark this variable as sensitive. Terraform will not show sensitive values in its CLI
+    /// output.
+    pub fn sensitive(&mut self, sensitive: bool) -> &mut Self {
+        self.variable.sensitive = Some(sensitive);
+        self
+    }
+
+    /// Set whether `null` is a valid value of this variable.
+    pub fn nullable(&mut self, nullable: bool) -> &mut Self {
+        self.variable.nullable = Some(nullable);
+        self
+    }
+
+    /// Add a validation rule to this variable. `condition` is a Terraform expression without
+    /// surrounding `${...}`, which must reference this variable (e.g. `length(var.name) > 3`).
+    pub fn validation(
+        &mut self,
+        condition: impl Into<String>,
+        error_message: impl Into<String>,
+    ) -> &mut Self {
+        let condition = condition.into();
+        let condition = self.stack.consume(|| reference::resolve_marks(&condition));
+        self.variable.validation.push(document::Validation {
+            condition: format!("${{{condition}}}"),
+            error_message: error_message.into(),
+        });
+        self
+    }
+
+    /// Add the configured variable to the stack. Returns a reference to this variable, which
+    /// can be passed to setters of resources.
+    pub fn build(&mut self) -> Value<T> {
+        self.stack
+            .insert_variable(self.name.clone(), self.variable.clone());
+        Value::Ref {
+            path: format!("var.{}", self.name),
+            value: None,
+            owner: Some(self.stack.name().to_string()),
+        }
+    }
+}
+
+impl Stack {
+    /// Create a new input variable `name` of this stack. The type constraint of this variable
+    /// is derived from `T`. The variable will only be added after calling
+    /// [`VariableBuilder::build`].
+    pub fn variable<T: VariableType>(&self, name: impl Into<String>) -> VariableBuilder<T> {
+        VariableBuilder {
+            stack: self.clone(),
+            name: name.into(),
+            variable: document::Variable {
+                ty: Some(T::type_constraint()),
+                default: None,
+                description: None,
+                sensitive: None,
+                nullable: None,
+                validation: Vec::new(),
+            },
+            _p: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::value::ValueSet;
+    use crate::{Stack, Value};
+
+    #[test]
+    fn variable_reference() {
+        let stack = Stack::new("test");
+        let value: Value<String> = stack
+            .variable::<String>("namespace")
+            .default("default")
+            .description("namespace to deploy to")
+            .build();
+        let value = serde_json::to_value(&value).unwrap();
+        assert_eq!(value, "${var.namespace}");
+        let document = stack.to_document();
+        let variable = &document.variable["namespace"];
+        assert_eq!(variable.ty.as_deref(), Some("string"));
+        assert_eq!(variable.default, Some(serde_json::json!("default")));
+    }
+
+    #[test]
+    fn variable_validation() {
+        let stack = Stack::new("test");
+        stack
+            .variable::<i64>("replicas")
+            .nullable(false)
+            .validation("var.replicas > 0", "at least one replica is required")
+            .build();
+        let document = stack.to_document();
+        let variable = serde_json::to_value(&document.variable["replicas"]).unwrap();
+        let expected = serde_json::json!({
+            "type": "number",
+            "nullable": false,
+            "validation": [{
+                "condition": "${var.replicas > 0}",
+                "error_message": "at least one replica is required"
+            }]
+        });
+        assert_eq!(variable, expected);
+    }
+
+    #[test]
+    fn variable_collection_types() {
+        let stack = Stack::new("test");
+        stack.variable::<Vec<String>>("zones").build();
+        stack.variable::<ValueSet<i64>>("ports").build();
+        stack.variable::<HashMap<String, f64>>("weights").build();
+        let document = stack.to_document();
+        assert_eq!(
+            document.variable["zones"].ty.as_deref(),
+            Some("list(string)")
+        );
+        assert_eq!(
+            document.variable["ports"].ty.as_deref(),
+            Some("set(number)")
+        );
+        assert_eq!(
+            document.variable["weights"].ty.as_deref(),
+            Some("map(number)")
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+fn main() {
+    println!("cargo:rerun-if-changed=fixtures");
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let bindings = tf_bindgen::Builder::default()
+        .config("fixtures/terraform.toml")
+        .schema_file("fixtures/schema.json")
+        .generate()
+        .unwrap();
+    let code = bindings
+        .importer()
+        .bindings_module("crate::terraform")
+        .import_path("fixtures/config")
+        .unwrap();
+    std::fs::write(out_dir.join("imported.rs"), code).unwrap();
+    bindings
+        .write_to_single_file(out_dir.join("terraform.rs"))
+        .unwrap();
+}
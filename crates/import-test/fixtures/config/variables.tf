@@ -0,0 +1,23 @@
+variable "replicas" {
+  type    = number
+  default = 2
+}
+
+locals {
+  name = "${kubernetes_pod.nginx.metadata[0].name}-copy"
+}
+
+resource "kubernetes_pod" "copy" {
+  metadata {
+    name = local.name
+  }
+  replicas = max(var.replicas, 1)
+
+  lifecycle {
+    create_before_destroy = true
+    postcondition {
+      condition     = self.id != ""
+      error_message = "missing id"
+    }
+  }
+}
@@ -0,0 +1,57 @@
+//! Compiles the code generated by the importer from `fixtures/config` against the bindings
+//! generated from `fixtures/schema.json` (see `build.rs`).
+
+pub mod terraform {
+    include!(concat!(env!("OUT_DIR"), "/terraform.rs"));
+}
+
+pub mod imported {
+    include!(concat!(env!("OUT_DIR"), "/imported.rs"));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use tf_bindgen::schema::document::Resource;
+    use tf_bindgen::Stack;
+
+    /// Returns the configuration of the object named `name` in the configuration.
+    fn find<'a>(objects: &'a BTreeMap<String, Resource>, name: &str) -> &'a Resource {
+        objects
+            .values()
+            .find(|object| object.meta.metadata.unique_id == name)
+            .unwrap_or_else(|| panic!("missing `{name}`"))
+    }
+
+    #[test]
+    fn imported_document() {
+        let stack = Stack::new("imported");
+        super::imported::build(&stack);
+        let document = stack.to_document();
+        assert_eq!(document.provider["kubernetes"].len(), 2);
+        assert_eq!(document.provider["kubernetes"][1]["alias"], "west");
+        assert_eq!(document.variable["replicas"].ty.as_deref(), Some("number"));
+        assert!(document.terraform.backend.contains_key("local"));
+        let namespace = find(&document.data["kubernetes_namespace"], "default");
+        assert_eq!(namespace.config["metadata"][0]["name"], "default");
+        let nginx = &find(&document.resource["kubernetes_pod"], "nginx").config;
+        assert_eq!(nginx["provider"], "kubernetes.west");
+        assert_eq!(nginx["replicas"], "${var.replicas}");
+        assert_eq!(nginx["lifecycle"]["ignore_changes"], "all");
+        assert_eq!(
+            nginx["lifecycle"]["precondition"][0]["condition"],
+            "${self.replicas > 0}"
+        );
+        let copy = &find(&document.resource["kubernetes_pod"], "copy").config;
+        assert_eq!(copy["metadata"][0]["name"], "${local.name}");
+        assert_eq!(
+            copy["lifecycle"]["postcondition"][0]["condition"],
+            "${self.id != \"\"}"
+        );
+        assert_eq!(
+            document.output["name"].description.as_deref(),
+            Some("Name of the pod")
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
@@ -9,14 +9,19 @@ pub struct Document {
     #[serde(rename = "//")]
     pub meta: Meta,
     pub terraform: Terraform,
-    pub provider: HashMap<String, Vec<Provider>>,
-    pub resource: HashMap<String, HashMap<String, Resource>>,
-    pub data: HashMap<String, HashMap<String, Resource>>,
+    pub provider: BTreeMap<String, Vec<Provider>>,
+    pub resource: BTreeMap<String, BTreeMap<String, Resource>>,
+    pub data: BTreeMap<String, BTreeMap<String, Resource>>,
+    pub output: BTreeMap<String, Output>,
+    pub variable: BTreeMap<String, Variable>,
+    pub locals: BTreeMap<String, serde_json::Value>,
 }
 
 #[derive(Deserialize, Serialize)]
 pub struct Terraform {
-    pub required_providers: HashMap<String, ProviderConfig>,
+    pub required_providers: BTreeMap<String, ProviderConfig>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub backend: BTreeMap<String, serde_json::Value>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -28,7 +33,7 @@ pub struct ProviderConfig {
 #[derive(Deserialize, Serialize)]
 pub struct Meta {
     pub metadata: Metadata,
-    pub outputs: HashMap<String, Output>,
+    pub outputs: BTreeMap<String, String>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -39,15 +44,45 @@ pub struct Metadata {
     pub version: String,
 }
 
-#[derive(Deserialize, Serialize)]
-pub struct Output {}
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Output {
+    pub value: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Variable {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub ty: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullable: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validation: Vec<Validation>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Validation {
+    pub condition: String,
+    pub error_message: String,
+}
 
 #[derive(Deserialize, Serialize)]
 pub struct Resource {
     #[serde(rename = "//")]
     pub meta: ResourceMeta,
     #[serde(flatten)]
-    pub config: HashMap<String, serde_json::Value>,
+    pub config: BTreeMap<String, serde_json::Value>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -60,4 +95,9 @@ pub struct ResourceMetadata {
     pub path: String,
     #[serde(rename = "uniqueId")]
     pub unique_id: String,
+    /// Paths of all nested blocks (e.g. `spec.container`). Used to distinguish blocks from
+    /// object attributes when writing Terraform's native syntax. Unknown if `None`. Not part of
+    /// the JSON configuration.
+    #[serde(skip)]
+    pub blocks: Option<Vec<String>>,
 }
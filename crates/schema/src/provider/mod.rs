@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
@@ -9,7 +9,7 @@ pub mod v1_0;
 pub enum Schema {
     #[serde(rename = "1.0")]
     V1_0 {
-        provider_schemas: HashMap<String, v1_0::Provider>,
+        provider_schemas: BTreeMap<String, v1_0::Provider>,
     },
     #[serde(other)]
     Unknown,
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use serde::de::Unexpected;
 use serde::ser::{SerializeMap, SerializeTuple};
@@ -7,8 +7,8 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Provider {
     pub provider: Schema,
-    pub resource_schemas: HashMap<String, Schema>,
-    pub data_source_schemas: HashMap<String, Schema>,
+    pub resource_schemas: BTreeMap<String, Schema>,
+    pub data_source_schemas: BTreeMap<String, Schema>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -19,8 +19,8 @@ pub struct Schema {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Block {
-    pub attributes: Option<HashMap<String, Attribute>>,
-    pub block_types: Option<HashMap<String, Type>>,
+    pub attributes: Option<BTreeMap<String, Attribute>>,
+    pub block_types: Option<BTreeMap<String, Type>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -48,7 +48,7 @@ pub struct Attribute {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct NestedType {
-    pub attributes: HashMap<String, Attribute>,
+    pub attributes: BTreeMap<String, Attribute>,
     pub nesting_mode: NestedTypeNesting,
 }
 
@@ -61,7 +61,7 @@ pub enum BlockType {
     Set(Box<BlockType>),
     Map(Box<BlockType>),
     List(Box<BlockType>),
-    Object(HashMap<String, BlockType>),
+    Object(BTreeMap<String, BlockType>),
 }
 
 #[derive(Debug)]
@@ -161,7 +161,7 @@ impl<'de> Deserialize<'de> for BlockType {
                     .next_element()?
                     .ok_or(serde::de::Error::invalid_length(0, &self))?;
                 if name == "object" {
-                    let attr: HashMap<String, BlockType> = seq
+                    let attr: BTreeMap<String, BlockType> = seq
                         .next_element()?
                         .ok_or(serde::de::Error::invalid_length(1, &self))?;
                     return Ok(BlockType::Object(attr));
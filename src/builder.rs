@@ -1,11 +1,16 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{anyhow, bail, Context, Result};
+use tf_bindgen_schema::provider::Schema;
 
-use crate::config::Config;
+use crate::cache::{read_terraform_lock, LockFile, LockedProvider, SchemaCache};
+use crate::config::{Config, ProviderConfig};
+use crate::filter::Filter;
 use crate::model::config::Terraform;
 use crate::model::Document;
 use crate::Bindings;
@@ -13,6 +18,17 @@ use crate::Bindings;
 #[derive(Default)]
 pub struct Builder {
     config_path: Option<String>,
+    schema: Option<SchemaSource>,
+    cache_dir: Option<PathBuf>,
+    disable_cache: bool,
+    filter: Filter,
+    feature_gates: bool,
+}
+
+/// Used to store where to read the provider schema from.
+enum SchemaSource {
+    File(PathBuf),
+    Json(String),
 }
 
 impl Builder {
@@ -29,6 +45,54 @@ impl Builder {
         self
     }
 
+    /// Read the provider schema from `path` instead of invoking Terraform. The file must
+    /// contain the output of `terraform providers schema -json`.
+    pub fn schema_file(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.schema = Some(SchemaSource::File(path.into()));
+        self
+    }
+
+    /// Use `json` as provider schema instead of invoking Terraform. See [`Builder::schema_file`].
+    pub fn schema_json(&mut self, json: impl Into<String>) -> &mut Self {
+        self.schema = Some(SchemaSource::Json(json.into()));
+        self
+    }
+
+    /// Set the directory used to cache provider schemas. Defaults to
+    /// [`SchemaCache::default_dir`].
+    pub fn cache_dir(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.cache_dir = Some(path.into());
+        self
+    }
+
+    /// Always use Terraform to read the provider schemas.
+    pub fn disable_cache(&mut self) -> &mut Self {
+        self.disable_cache = true;
+        self
+    }
+
+    /// Only generate resources and data sources matching `pattern` (e.g. `kubernetes_pod*`).
+    /// Applies to all providers without allow list of their own, if any of their types matches
+    /// a pattern. Other providers are generated as is. See [`Filter::inherit`].
+    pub fn allow(&mut self, pattern: impl Into<String>) -> &mut Self {
+        self.filter.allow(pattern);
+        self
+    }
+
+    /// Skip resources and data sources matching `pattern`. Applies to all providers. See
+    /// [`Filter`].
+    pub fn deny(&mut self, pattern: impl Into<String>) -> &mut Self {
+        self.filter.deny(pattern);
+        self
+    }
+
+    /// Only compile a resource or data source module if the matching cargo feature is enabled.
+    /// See [`Bindings::write_to_file`].
+    pub fn feature_gates(&mut self) -> &mut Self {
+        self.feature_gates = true;
+        self
+    }
+
     /// Read configuration file and generate rust files from terraform providers.
     pub fn generate(&mut self) -> Result<Bindings> {
         let config_path = self
@@ -37,52 +101,455 @@ impl Builder {
             .ok_or(anyhow!("missing config path"))?;
         let cfg = Config::from_file(&config_path)
             .with_context(|| format!("failed to read config from file {config_path}"))?;
-        let providers = cfg.providers().context("failed to parse providers")?;
-        let version = providers.iter().cloned().collect();
+        let mut providers = cfg.providers().context("failed to parse providers")?;
+        let schema = match self.schema.take() {
+            Some(SchemaSource::File(path)) => {
+                let json = std::fs::read_to_string(&path).with_context(|| {
+                    format!(
+                        "failed to read provider schema from file {}",
+                        path.display()
+                    )
+                })?;
+                serde_json::from_str(&json).context("failed to parse provider schema")?
+            }
+            Some(SchemaSource::Json(json)) => {
+                serde_json::from_str(&json).context("failed to parse provider schema")?
+            }
+            None => {
+                let lock_path = Path::new(&config_path).with_extension("lock");
+                let cache = if self.disable_cache {
+                    None
+                } else {
+                    self.cache_dir
+                        .take()
+                        .or_else(SchemaCache::default_dir)
+                        .map(SchemaCache::new)
+                };
+                read_cached_schema(&lock_path, cache, &providers)?
+            }
+        };
+        check_providers(&schema, &providers)?;
+        if let Schema::V1_0 { provider_schemas } = &schema {
+            for (url, schema) in provider_schemas {
+                let types = schema
+                    .resource_schemas
+                    .keys()
+                    .chain(schema.data_source_schemas.keys())
+                    .map(String::as_str);
+                for provider in providers
+                    .iter_mut()
+                    .filter(|provider| provider.matches(url))
+                {
+                    provider.filter.inherit(&self.filter, types.clone());
+                }
+            }
+        }
 
-        let terraform_dir = Path::new(&std::env::var("OUT_DIR").unwrap()).join("terraform");
-        std::fs::create_dir_all(&terraform_dir).context("failed to create terraform directory")?;
-        let main_file = terraform_dir.join("main.tf.json");
+        Ok(Bindings {
+            schema,
+            providers,
+            feature_gates: self.feature_gates,
+        })
+    }
+}
 
-        let mut config = Terraform::default();
-        for (name, constraint) in &providers {
-            config.add_provider(name, constraint.clone())
+/// Ensure every provider of `schema` was specified in the configuration file.
+fn check_providers(schema: &Schema, providers: &[ProviderConfig]) -> Result<()> {
+    let provider_schemas = match schema {
+        Schema::V1_0 { provider_schemas } => provider_schemas,
+        Schema::Unknown => bail!("unsupported provider schema version"),
+    };
+    for url in provider_schemas.keys() {
+        if !providers.iter().any(|provider| provider.matches(url)) {
+            bail!("provider `{url}` is missing in config file")
         }
-        let document = Document::from_config(config);
-
-        let file = File::create(main_file).context("failed to write main bindings file")?;
-        let mut writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(&mut writer, &document).unwrap();
-        writer
-            .flush()
-            .context("failed to write to terraform provider document")?;
-
-        let tf_process = Command::new("terraform")
-            .arg(format!("-chdir={}", terraform_dir.to_str().unwrap()))
-            .arg("init")
-            .output()
-            .context("failed to initialize terraform provider")?;
-        if !tf_process.status.success() {
-            print!("{}", String::from_utf8(tf_process.stdout).unwrap());
-            print!("{}", String::from_utf8(tf_process.stderr).unwrap());
-            panic!("failed to initialize Terraform")
+    }
+    Ok(())
+}
+
+/// Read the schema of `providers` from `cache` if all locked versions are cached. Otherwise,
+/// Terraform is used to resolve the provider versions. Resolved versions will be written to the
+/// lock file at `lock_path`.
+fn read_cached_schema(
+    lock_path: &Path,
+    cache: Option<SchemaCache>,
+    providers: &[ProviderConfig],
+) -> Result<Schema> {
+    let mut lock = LockFile::from_file(lock_path)
+        .with_context(|| format!("failed to load lock file {}", lock_path.display()))?;
+    if let Some(cache) = &cache {
+        let provider_schemas = providers
+            .iter()
+            .map(|provider| {
+                let locked = lock.get(provider)?;
+                let schema = cache.get(&locked.source, &locked.version)?;
+                Some((locked.source.clone(), schema))
+            })
+            .collect();
+        if let Some(provider_schemas) = provider_schemas {
+            return Ok(Schema::V1_0 { provider_schemas });
         }
+    }
 
-        let tf_process = Command::new("terraform")
-            .arg(format!("-chdir={}", terraform_dir.to_str().unwrap()))
-            .arg("providers")
-            .arg("schema")
-            .arg("-json")
-            .output()
-            .context("failed to read terraform provider schemas")?;
-        if !tf_process.status.success() {
-            print!("{}", String::from_utf8(tf_process.stdout).unwrap());
-            print!("{}", String::from_utf8(tf_process.stderr).unwrap());
-            bail!("failed to read terraform provider schema")
+    let (schema, versions) = read_schema(providers)?;
+    let provider_schemas = match &schema {
+        Schema::V1_0 { provider_schemas } => provider_schemas,
+        Schema::Unknown => bail!("unsupported provider schema version"),
+    };
+    for provider in providers {
+        let Some((source, version)) = versions.iter().find(|(source, _)| provider.matches(source))
+        else {
+            bail!("failed to resolve version of provider `{}`", provider.name)
+        };
+        if let (Some(cache), Some(schema)) = (&cache, provider_schemas.get(source)) {
+            cache.insert(source, version, schema)?;
         }
-        let schema = serde_json::from_slice(&tf_process.stdout[..])
-            .context("failed to parse provider schema")?;
+        let locked = LockedProvider {
+            source: source.clone(),
+            constraint: provider.version.to_string(),
+            version: version.clone(),
+        };
+        lock.insert(provider.name.clone(), locked);
+    }
+    let names: Vec<_> = providers
+        .iter()
+        .map(|provider| provider.name.as_str())
+        .collect();
+    lock.retain(&names);
+    lock.write_to_file(lock_path)
+        .with_context(|| format!("failed to write lock file {}", lock_path.display()))?;
+    Ok(schema)
+}
+
+/// Use Terraform to download `providers` and read their schema. Returns the schema and the
+/// selected version of each provider.
+fn read_schema(providers: &[ProviderConfig]) -> Result<(Schema, HashMap<String, String>)> {
+    // Will use a temporary directory if not invoked from a build script.
+    let out_dir = std::env::var_os("OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("tf-bindgen"));
+    let terraform_dir = out_dir.join("terraform");
+    std::fs::create_dir_all(&terraform_dir).context("failed to create terraform directory")?;
+    let main_file = terraform_dir.join("main.tf.json");
+
+    let mut config = Terraform::default();
+    for provider in providers {
+        config.add_provider(provider)
+    }
+    let document = Document::from_config(config);
+
+    let file = File::create(main_file).context("failed to write main bindings file")?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, &document)
+        .context("failed to write terraform provider document")?;
+    writer
+        .flush()
+        .context("failed to write to terraform provider document")?;
+
+    terraform(&terraform_dir, &["init"]).context("failed to initialize Terraform")?;
+    let schema = terraform(&terraform_dir, &["providers", "schema", "-json"])
+        .context("failed to read terraform provider schema")?;
+    let versions = read_terraform_lock(terraform_dir.join(".terraform.lock.hcl"))?;
+    let schema = serde_json::from_slice(&schema).context("failed to parse provider schema")?;
+    Ok((schema, versions))
+}
+
+/// Run Terraform with `args` inside of `dir`. Returns the standard output.
+///
+/// # Errors
+///
+/// Will return `Err` if Terraform could not be run or failed. The error will contain the output
+/// of Terraform.
+fn terraform(dir: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let mut chdir = OsString::from("-chdir=");
+    chdir.push(dir);
+    let output = Command::new("terraform")
+        .arg(chdir)
+        .args(args)
+        .output()
+        .context("failed to run terraform")?;
+    if !output.status.success() {
+        bail!(
+            "terraform {} failed ({}):\n{}{}",
+            args.join(" "),
+            output.status,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    use tempfile::TempDir;
+
+    use super::Builder;
+    use crate::cache::{LockFile, LockedProvider, SchemaCache};
+
+    const KUBERNETES: &str = "[provider]\nkubernetes = \"=2.19.0\"";
+
+    /// Create a provider schema of the kubernetes provider containing `resources`. Every
+    /// resource uses `block` as its schema.
+    fn schema(resources: &[&str], block: &str) -> String {
+        let resources = resources
+            .iter()
+            .map(|ty| format!(r#""{ty}": {{ "version": 0, "block": {block} }}"#))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            r#"{{
+                "format_version": "1.0",
+                "provider_schemas": {{
+                    "registry.terraform.io/hashicorp/kubernetes": {{
+                        "provider": {{ "version": 0, "block": {{}} }},
+                        "resource_schemas": {{ {resources} }},
+                        "data_source_schemas": {{}}
+                    }}
+                }}
+            }}"#
+        )
+    }
+
+    /// Write the configuration file `content` to `dir`. Returns the path of the written file.
+    fn write_config(dir: &TempDir, content: &str) -> String {
+        let path = dir.path().join("terraform.toml");
+        std::fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn offline_schema() {
+        let dir = TempDir::new().unwrap();
+        let config = write_config(&dir, "[provider]\n\"hashicorp/kubernetes\" = \"=2.19.0\"");
+        let bindings = Builder::default()
+            .config(config)
+            .schema_json(schema(&[], "{}"))
+            .generate()
+            .unwrap();
+        assert_eq!(bindings.providers.len(), 1);
+    }
+
+    #[test]
+    fn offline_schema_missing_provider() {
+        let dir = TempDir::new().unwrap();
+        let config = write_config(&dir, "[provider]\n\"kreuzwerker/docker\" = \"=3.0.2\"");
+        let result = Builder::default()
+            .config(config)
+            .schema_json(schema(&[], "{}"))
+            .generate();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn feature_gates() {
+        let dir = TempDir::new().unwrap();
+        let config = write_config(&dir, KUBERNETES);
+        let out = dir.path();
+        Builder::default()
+            .config(config)
+            .schema_json(schema(&["kubernetes_pod"], "{}"))
+            .feature_gates()
+            .generate()
+            .unwrap()
+            .write_to_file(out, "terraform.rs")
+            .unwrap();
+        let features = std::fs::read_to_string(out.join("features.toml")).unwrap();
+        assert_eq!(
+            features,
+            "[features]\nkubernetes = [\"kubernetes_pod\"]\nkubernetes_pod = []\n"
+        );
+        let module = std::fs::read_to_string(out.join("provider/kubernetes/mod.rs")).unwrap();
+        assert!(
+            module.contains("#[cfg(feature = \"kubernetes_pod\")]\n    pub mod kubernetes_pod;")
+        );
+    }
+
+    #[test]
+    fn deterministic_output() {
+        let dir = TempDir::new().unwrap();
+        let config = write_config(&dir, KUBERNETES);
+        let block = r#"{
+            "attributes": {
+                "name": { "type": "string", "optional": true },
+                "labels": { "type": ["map", "string"], "optional": true },
+                "replicas": { "type": "number", "optional": true },
+                "uid": { "type": "string", "computed": true },
+                "ports": { "type": ["list", ["object", { "name": "string", "port": "number" }]], "optional": true }
+            },
+            "block_types": {
+                "metadata": { "nesting_mode": "list", "block": { "attributes": { "name": { "type": "string", "optional": true } } } },
+                "spec": { "nesting_mode": "single", "block": { "attributes": { "image": { "type": "string", "optional": true } } } }
+            }
+        }"#;
+        let schema = schema(
+            &[
+                "kubernetes_pod",
+                "kubernetes_service",
+                "kubernetes_namespace",
+            ],
+            block,
+        );
+        let out = dir.path().join("out");
+        let generate = || {
+            let _ = std::fs::remove_dir_all(&out);
+            std::fs::create_dir_all(&out).unwrap();
+            Builder::default()
+                .config(&config)
+                .schema_json(&schema)
+                .generate()
+                .unwrap()
+                .write_to_file(&out, "terraform.rs")
+                .unwrap();
+            read_dir(&out)
+        };
+        let first = generate();
+        let second = generate();
+        assert_eq!(first.len(), 5);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn allow_other_provider() {
+        let dir = TempDir::new().unwrap();
+        let config = write_config(
+            &dir,
+            "[provider]\nkubernetes = \"=2.19.0\"\n\"kreuzwerker/docker\" = \"=3.0.2\"",
+        );
+        let schema = r#"{
+            "format_version": "1.0",
+            "provider_schemas": {
+                "registry.terraform.io/hashicorp/kubernetes": {
+                    "provider": { "version": 0, "block": {} },
+                    "resource_schemas": {
+                        "kubernetes_pod": { "version": 0, "block": {} },
+                        "kubernetes_service": { "version": 0, "block": {} }
+                    },
+                    "data_source_schemas": {}
+                },
+                "registry.terraform.io/kreuzwerker/docker": {
+                    "provider": { "version": 0, "block": {} },
+                    "resource_schemas": { "docker_image": { "version": 0, "block": {} } },
+                    "data_source_schemas": {}
+                }
+            }
+        }"#;
+        let report = Builder::default()
+            .config(config)
+            .schema_json(schema)
+            .allow("kubernetes_pod")
+            .generate()
+            .unwrap()
+            .write_to_single_file(dir.path().join("terraform.rs"))
+            .unwrap();
+        let mut resources: Vec<_> = report
+            .providers
+            .iter()
+            .map(|provider| (provider.name.as_str(), provider.resources))
+            .collect();
+        resources.sort();
+        assert_eq!(resources, [("docker", 1), ("kubernetes", 1)]);
+    }
+
+    #[test]
+    fn relative_root_file() {
+        let dir = TempDir::new().unwrap();
+        let config = write_config(&dir, KUBERNETES);
+        let out = dir.path();
+        std::fs::create_dir_all(out.join("src")).unwrap();
+        let bindings = || {
+            Builder::default()
+                .config(&config)
+                .schema_json(schema(&[], "{}"))
+                .generate()
+                .unwrap()
+        };
+        bindings().write_to_file(out, "src/terraform.rs").unwrap();
+        let root = std::fs::read_to_string(out.join("src/terraform.rs")).unwrap();
+        assert_eq!(
+            root,
+            "#[path = \"../provider/kubernetes/mod.rs\"]\npub mod kubernetes;\n"
+        );
+        let err = bindings()
+            .write_to_file(out, out.join("terraform.rs"))
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn single_file() {
+        let dir = TempDir::new().unwrap();
+        let config = write_config(&dir, KUBERNETES);
+        let path = dir.path().join("terraform.rs");
+        Builder::default()
+            .config(config)
+            .schema_json(schema(&["kubernetes_pod"], "{}"))
+            .generate()
+            .unwrap()
+            .write_to_single_file(&path)
+            .unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.starts_with("pub mod kubernetes {"));
+        assert!(content.contains("pub mod kubernetes_pod {"));
+        assert!(!content.contains("#[path"));
+    }
+
+    /// Read all files in `dir` recursively. Returns a map from relative path to content.
+    fn read_dir(dir: &Path) -> BTreeMap<PathBuf, String> {
+        let mut files = BTreeMap::new();
+        let mut dirs = vec![dir.to_path_buf()];
+        while let Some(current) = dirs.pop() {
+            for entry in std::fs::read_dir(current).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else {
+                    let content = std::fs::read_to_string(&path).unwrap();
+                    files.insert(path.strip_prefix(dir).unwrap().to_path_buf(), content);
+                }
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn cached_schema() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let config = write_config(&temp, "[provider]\n\"hashicorp/kubernetes\" = \"=2.19.0\"");
+        let source = "registry.terraform.io/hashicorp/kubernetes";
+        let mut lock = LockFile::default();
+        lock.insert(
+            "hashicorp/kubernetes",
+            LockedProvider {
+                source: source.to_string(),
+                constraint: "=2.19.0".to_string(),
+                version: "2.19.0".to_string(),
+            },
+        );
+        lock.write_to_file(dir.join("terraform.lock")).unwrap();
+        let schema: serde_json::Value = serde_json::from_str(&schema(&[], "{}")).unwrap();
+        let schema = serde_json::from_value(schema["provider_schemas"][source].clone()).unwrap();
+        let cache = SchemaCache::new(dir.join("cache"));
+        cache.insert(source, "2.19.0", &schema).unwrap();
+
+        let bindings = Builder::default()
+            .config(config)
+            .cache_dir(dir.join("cache"))
+            .generate()
+            .unwrap();
+        assert_eq!(bindings.providers.len(), 1);
+    }
 
-        Ok(Bindings { schema, version })
+    #[test]
+    fn terraform_failure() {
+        let dir = TempDir::new().unwrap();
+        let err = super::terraform(dir.path(), &["not-a-command"]).unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("terraform not-a-command failed"));
+        assert!(message.contains("no command named \"not-a-command\""));
     }
 }
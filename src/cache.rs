@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tf_bindgen_schema::provider::v1_0::Provider;
+
+use crate::config::ProviderConfig;
+
+/// Used to store extracted provider schemas across builds. Schemas are stored as
+/// `<dir>/<source>/<version>.json` (e.g.
+/// `registry.terraform.io/hashicorp/kubernetes/2.19.0.json`).
+pub struct SchemaCache {
+    dir: PathBuf,
+}
+
+/// Used to record which provider versions were resolved for a configuration file.
+///
+/// # Lock File
+///
+/// ```toml
+/// [provider."hashicorp/kubernetes"]
+/// source = "registry.terraform.io/hashicorp/kubernetes"
+/// constraint = "=2.19.0"
+/// version = "2.19.0"
+/// ```
+#[derive(Default, Deserialize, Serialize)]
+pub struct LockFile {
+    provider: BTreeMap<String, LockedProvider>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct LockedProvider {
+    pub source: String,
+    pub constraint: String,
+    pub version: String,
+}
+
+impl SchemaCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the default cache directory. Will use `TF_BINDGEN_CACHE_DIR` if set. Otherwise
+    /// `$XDG_CACHE_HOME/tf-bindgen` or `$HOME/.cache/tf-bindgen` is used.
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Some(dir) = std::env::var_os("TF_BINDGEN_CACHE_DIR") {
+            return Some(dir.into());
+        }
+        if let Some(dir) = std::env::var_os("XDG_CACHE_HOME") {
+            return Some(Path::new(&dir).join("tf-bindgen"));
+        }
+        std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache").join("tf-bindgen"))
+    }
+
+    fn path(&self, source: &str, version: &str) -> PathBuf {
+        self.dir.join(source).join(format!("{version}.json"))
+    }
+
+    /// Returns the cached schema of provider `source` with version `version`. Will return
+    /// `None` if the schema is not cached or the cache entry cannot be parsed.
+    pub fn get(&self, source: &str, version: &str) -> Option<Provider> {
+        let content = std::fs::read_to_string(self.path(source, version)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Store `schema` of provider `source` with version `version`.
+    pub fn insert(&self, source: &str, version: &str, schema: &Provider) -> Result<()> {
+        let path = self.path(source, version);
+        std::fs::create_dir_all(path.parent().unwrap())
+            .context("failed to create schema cache directory")?;
+        let content = serde_json::to_string(schema).context("failed to serialize schema")?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("failed to write schema cache {}", path.display()))
+    }
+}
+
+impl LockFile {
+    /// Load lock file from `path`. Will return an empty lock file if `path` does not exist.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        if !path.as_ref().exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path).context("failed to read lock file")?;
+        toml::from_str(&content).context("failed to parse lock file")
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let content = toml::to_string(self).context("failed to serialize lock file")?;
+        std::fs::write(path, content).context("failed to write lock file")
+    }
+
+    /// Returns the locked version of `provider`. Will return `None` if the provider is not
+    /// locked or was locked using a different source or version constraint.
+    pub fn get(&self, provider: &ProviderConfig) -> Option<&LockedProvider> {
+        self.provider.get(&provider.name).filter(|locked| {
+            locked.constraint == provider.version.to_string() && provider.matches(&locked.source)
+        })
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, provider: LockedProvider) {
+        self.provider.insert(name.into(), provider);
+    }
+
+    /// Remove all providers not contained in `names`.
+    pub fn retain(&mut self, names: &[&str]) {
+        self.provider
+            .retain(|name, _| names.contains(&name.as_str()))
+    }
+}
+
+/// Read the provider versions selected by Terraform from a `.terraform.lock.hcl` file.
+/// Returns a map from provider source to version.
+pub fn read_terraform_lock(path: impl AsRef<Path>) -> Result<HashMap<String, String>> {
+    let content =
+        std::fs::read_to_string(path).context("failed to read terraform dependency lock file")?;
+    let mut versions = HashMap::new();
+    let mut source = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("provider ") {
+            source = Some(
+                rest.trim_end_matches('{')
+                    .trim()
+                    .trim_matches('"')
+                    .to_string(),
+            );
+        } else if let Some(rest) = line.strip_prefix("version") {
+            let version = rest
+                .trim_start()
+                .trim_start_matches('=')
+                .trim()
+                .trim_matches('"');
+            if let Some(source) = source.take() {
+                versions.insert(source, version.to_string());
+            }
+        }
+    }
+    Ok(versions)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::read_terraform_lock;
+
+    #[test]
+    fn terraform_lock() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".terraform.lock.hcl");
+        let content = r#"
+# This file is maintained automatically by "terraform init".
+provider "registry.terraform.io/hashicorp/kubernetes" {
+  version     = "2.19.0"
+  constraints = ">= 0.0.0, 2.19.0"
+  hashes = [
+    "h1:abc=",
+  ]
+}
+"#;
+        std::fs::write(&path, content).unwrap();
+        let versions = read_terraform_lock(&path).unwrap();
+        assert_eq!(
+            versions["registry.terraform.io/hashicorp/kubernetes"],
+            "2.19.0"
+        );
+    }
+}
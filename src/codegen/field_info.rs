@@ -1,4 +1,6 @@
 use itertools::Itertools;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
 
 use super::{
     path::Path,
@@ -20,28 +22,40 @@ impl FieldInfo {
         FieldInfoBuilder::default()
     }
 
-    pub fn gen_field(&self) -> String {
-        let name = self.name();
+    pub fn gen_field(&self) -> TokenStream {
+        let name = self.ident();
+        let raw_name = self.raw_name();
+        let rename = (self.name() != raw_name && !self.name().starts_with("r#"))
+            .then(|| quote!(#[serde(rename = #raw_name)]));
         if self.is_computed() && !self.is_optional() {
             let type_name = self.type_info.source();
-            return format!(
-                "#[serde(skip_serializing)] pub {name}: ::tf_bindgen::value::Cell<::tf_bindgen::value::Computed<{type_name}>>"
-            );
+            return quote! {
+                #[serde(skip_serializing)]
+                pub #name: ::tf_bindgen::value::Cell<::tf_bindgen::value::Computed<#type_name>>
+            };
         }
         let type_name = self.field_type();
-        format!("pub {name}: {type_name}")
+        quote!(#rename pub #name: #type_name)
     }
 
-    pub fn gen_builder_field(&self) -> String {
-        let name = self.name();
+    pub fn gen_builder_field(&self) -> TokenStream {
+        let name = self.ident();
         let type_name = self.type_info.source();
-        format!("{name}: ::std::option::Option<{type_name}>")
+        quote!(#name: ::std::option::Option<#type_name>)
     }
 
     pub fn name(&self) -> &str {
         fix_ident(&self.name)
     }
 
+    /// Returns the identifier used for this field.
+    pub fn ident(&self) -> Ident {
+        match self.name().strip_prefix("r#") {
+            Some(name) => Ident::new_raw(name, Span::call_site()),
+            None => Ident::new(self.name(), Span::call_site()),
+        }
+    }
+
     pub fn raw_name(&self) -> &str {
         &self.name
     }
@@ -58,16 +72,13 @@ impl FieldInfo {
 
     /// Name of the reference used by terraform.
     pub fn path_ref(&self) -> String {
-        self.path
-            .segments()
-            .chain(Some(&self.name).into_iter())
-            .join(".")
+        self.path.segments().chain(Some(&self.name)).join(".")
     }
 
-    fn ty(&self) -> String {
+    fn ty(&self) -> TokenStream {
         let type_name = self.type_info.source();
         if self.is_optional() {
-            format!("::std::option::Option<{type_name}>")
+            quote!(::std::option::Option<#type_name>)
         } else {
             type_name
         }
@@ -75,48 +86,51 @@ impl FieldInfo {
 
     /// Type of field used inside of resources and nested types. Will be wrapped inside of
     /// [`std::rc::Rc`] and [`crate::value::Cell`].
-    pub fn field_type(&self) -> String {
+    pub fn field_type(&self) -> TokenStream {
         let type_name = self.ty();
-        format!("::tf_bindgen::value::Cell<{type_name}>")
+        quote!(::tf_bindgen::value::Cell<#type_name>)
     }
 
     /// Generated the builder's setter function.
-    pub fn builder_setter_impl(&self) -> String {
-        let name = self.name();
-        let fn_name = match name {
-            "build" => "build_",
-            _ => name,
+    pub fn builder_setter_impl(&self) -> TokenStream {
+        let name = self.ident();
+        let fn_name = match self.name() {
+            "build" => format_ident!("build_"),
+            _ => name.clone(),
         };
         let type_name = self.type_info.type_name();
         let convert = match self.type_info.wrapper() {
-            Wrapper::List => "into_value_list()",
-            Wrapper::Map => "into_value_map()",
-            Wrapper::Type => "into_value()",
-            Wrapper::Set => "into_value_set()",
+            Wrapper::List => quote!(into_value_list),
+            Wrapper::Map => quote!(into_value_map),
+            Wrapper::Type => quote!(into_value),
+            Wrapper::Set => quote!(into_value_set),
         };
         let impl_type = match self.type_info.wrapper() {
-            Wrapper::List => "IntoValueList",
-            Wrapper::Map => "IntoValueMap",
-            Wrapper::Type => "IntoValue",
-            Wrapper::Set => "IntoValueSet",
+            Wrapper::List => quote!(IntoValueList),
+            Wrapper::Map => quote!(IntoValueMap),
+            Wrapper::Type => quote!(IntoValue),
+            Wrapper::Set => quote!(IntoValueSet),
         };
         let body_impl = match self.type_info.wrapper() {
-            Wrapper::List => format!(
-                r#"let new_list = value.{convert};
-				if let Some(list) = &mut self.{name} {{
-					list.extend(new_list);
-				}} else {{
-					self.{name} = Some(new_list);
-				}}
-				self"#
-            ),
-            _ => format!(r#"self.{name} = Some(value.{convert}); self"#),
+            Wrapper::List => quote! {
+                let new_list = value.#convert();
+                if let Some(list) = &mut self.#name {
+                    list.extend(new_list);
+                } else {
+                    self.#name = Some(new_list);
+                }
+                self
+            },
+            _ => quote! {
+                self.#name = Some(value.#convert());
+                self
+            },
         };
-        format!(
-            r#"pub fn {fn_name}(&mut self, value: impl ::tf_bindgen::value::{impl_type}<{type_name}>) -> &mut Self {{
-				{body_impl}
-			}}"#
-        )
+        quote! {
+            pub fn #fn_name(&mut self, value: impl ::tf_bindgen::value::#impl_type<#type_name>) -> &mut Self {
+                #body_impl
+            }
+        }
     }
 
     /// Generate doc comment for field builder. Will be empty if node description was specified.
@@ -129,11 +143,14 @@ impl FieldInfo {
     }
 }
 
-/// Replace rust keywords with raw names.
-fn fix_ident(input: &str) -> &str {
+/// Replace rust keywords with raw names. Keywords which cannot be used as raw identifiers
+/// (e.g. `self`) are suffixed with `_` instead.
+pub(crate) fn fix_ident(input: &str) -> &str {
     assert!(!input.is_empty(), "ident: '{input}' is empty");
     match input {
         "type" => "r#type",
+        "Self" => "Self_",
+        "crate" => "crate_",
         "as" => "r#as",
         "async" => "r#async",
         "await" => "r#await",
@@ -145,7 +162,7 @@ fn fix_ident(input: &str) -> &str {
         "else" => "r#else",
         "enum" => "r#enum",
         "extern" => "r#extern",
-        "fn" => "r#final",
+        "fn" => "r#fn",
         "for" => "r#for",
         "if" => "r#if",
         "impl" => "r#impl",
@@ -160,9 +177,9 @@ fn fix_ident(input: &str) -> &str {
         "pub" => "r#pub",
         "ref" => "r#ref",
         "return" => "r#return",
-        "self" => "r#self",
+        "self" => "self_",
         "static" => "r#static",
-        "super" => "r#super",
+        "super" => "super_",
         "trait" => "r#trait",
         "union" => "r#union",
         "unsafe" => "r#unsafe",
@@ -173,3 +190,42 @@ fn fix_ident(input: &str) -> &str {
         _ => input,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::{fix_ident, FieldInfo, Path, TypeInfo, Wrapper};
+
+    #[test]
+    fn keywords() {
+        assert_eq!(fix_ident("type"), "r#type");
+        assert_eq!(fix_ident("fn"), "r#fn");
+        assert_eq!(fix_ident("self"), "self_");
+        assert_eq!(fix_ident("Self"), "Self_");
+        assert_eq!(fix_ident("metadata"), "metadata");
+    }
+
+    #[test]
+    fn keyword_fields() {
+        for name in ["type", "fn", "self", "super", "Self", "crate"] {
+            let field = FieldInfo::builder()
+                .path(Path::empty())
+                .name(name.to_string())
+                .type_info(TypeInfo::new(Wrapper::Type, quote!(String)))
+                .description(None)
+                .optional(true)
+                .computed(false)
+                .build()
+                .unwrap();
+            let tokens = field.gen_field().to_string();
+            assert!(tokens.contains(&field.ident().to_string()), "{tokens}");
+            let renamed = tokens.contains(&format!("rename = \"{name}\""));
+            assert_eq!(
+                renamed,
+                field.ident().to_string().ends_with('_'),
+                "{tokens}"
+            );
+        }
+    }
+}
@@ -1,12 +1,13 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use heck::ToUpperCamelCase;
-use itertools::Itertools;
-use semver::{Comparator, Op, VersionReq};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
 use tf_bindgen_schema::provider::v1_0::{Attribute, Block, BlockType, Type};
 use tf_bindgen_schema::provider::Schema;
 
 use crate::codegen::type_info::TypeInfo;
+use crate::config::ProviderConfig;
 
 use self::field_info::FieldInfo;
 use self::path::Path;
@@ -27,44 +28,49 @@ pub struct Generator {
 
 #[derive(Debug)]
 pub struct Provider {
+    /// Name of the generated Rust module.
+    pub module_name: String,
     pub provider: StructInfo,
     pub resources: Vec<StructInfo>,
     pub data_sources: Vec<StructInfo>,
 }
 
 pub struct Nested(Vec<StructInfo>);
-pub struct Constructs(Vec<StructInfo>);
 pub struct Fields(Vec<FieldInfo>);
 
 impl Generator {
-    pub fn from_schema(schema: Schema, versions: HashMap<String, VersionReq>) -> Self {
+    /// Create code generators for all providers in `schema`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a provider of `schema` is missing in `providers`.
+    pub fn from_schema(schema: Schema, providers: &[ProviderConfig]) -> Self {
         let schemas = match schema {
             Schema::V1_0 { provider_schemas } => provider_schemas
                 .iter()
                 .map(|(url, schema)| {
-                    let name = url.split('/').last().unwrap();
-                    let version = versions
-                        .iter()
-                        .find(|(n, _)| n.split('/').last().unwrap() == name)
-                        .unwrap()
-                        .1
-                        .comparators
+                    let config = providers
                         .iter()
-                        .map(|comp| cargo_simplify_version(comp.clone()))
-                        .join(",");
+                        .find(|provider| provider.matches(url))
+                        .unwrap_or_else(|| panic!("missing configuration of provider `{url}`"));
+                    let name = url.split('/').last().unwrap();
+                    let version = config.version.to_terraform();
                     let provider =
                         StructInfo::from_provider(name, version, url, &schema.provider.block);
                     let resources = schema
                         .resource_schemas
                         .iter()
-                        .map(|(name, schema)| {
+                        .filter(|(ty, _)| config.filter.is_match(ty))
+                        .map(|(ty, schema)| {
+                            let name = config.rename.get(ty).unwrap_or(ty);
                             let path = Path::empty();
                             let this_path = Path::new(vec![name.to_string()]);
                             let fields = Fields::from_schema(&this_path, &schema.block).0;
                             let nested = Nested::from_schema(&this_path, &schema.block).0;
-                            let ty = StructType::Construct {
-                                ty: name.clone(),
+                            let ty = StructType::Resource {
+                                ty: ty.clone(),
                                 nested,
+                                blocks: block_paths(&schema.block),
                             };
                             StructInfo::builder()
                                 .ty(ty)
@@ -78,13 +84,16 @@ impl Generator {
                     let data_sources = schema
                         .data_source_schemas
                         .iter()
-                        .map(|(name, schema)| {
+                        .filter(|(ty, _)| config.filter.is_match(ty))
+                        .map(|(ty, schema)| {
+                            let name = config.rename.get(ty).unwrap_or(ty);
                             let path = Path::new(vec!["data".to_string()]);
                             let this_path = Path::new(vec!["data".to_string(), name.to_string()]);
                             let nested = Nested::from_schema(&this_path, &schema.block).0;
-                            let ty = StructType::Construct {
-                                ty: name.clone(),
+                            let ty = StructType::DataSource {
+                                ty: ty.clone(),
                                 nested,
+                                blocks: block_paths(&schema.block),
                             };
                             let fields = Fields::from_schema(&this_path, &schema.block).0;
                             StructInfo::builder()
@@ -97,6 +106,7 @@ impl Generator {
                         })
                         .collect();
                     Provider {
+                        module_name: config.module_name().to_string(),
                         provider,
                         resources,
                         data_sources,
@@ -135,7 +145,26 @@ impl StructInfo {
     }
 }
 
-fn get_fields(ty: &BlockType) -> Option<&HashMap<String, BlockType>> {
+/// Returns the paths of all nested blocks of `block` (e.g. `spec.container`).
+fn block_paths(block: &Block) -> Vec<String> {
+    block
+        .block_types
+        .iter()
+        .flatten()
+        .flat_map(|(name, ty)| {
+            let block = match ty {
+                Type::Single { block } => block,
+                Type::List { block, .. } => block,
+            };
+            let nested = block_paths(block)
+                .into_iter()
+                .map(move |path| format!("{name}.{path}"));
+            std::iter::once(name.clone()).chain(nested)
+        })
+        .collect()
+}
+
+fn get_fields(ty: &BlockType) -> Option<&BTreeMap<String, BlockType>> {
     match ty {
         BlockType::Set(inner) | BlockType::Map(inner) | BlockType::List(inner) => get_fields(inner),
         BlockType::Object(fields) => Some(fields),
@@ -185,7 +214,7 @@ impl Nested {
         Nested(nested)
     }
 
-    pub fn from_fields(path: &Path, fields: &HashMap<String, BlockType>) -> Self {
+    pub fn from_fields(path: &Path, fields: &BTreeMap<String, BlockType>) -> Self {
         let nested = fields
             .iter()
             .filter_map(|(name, ty)| Some((name, get_fields(ty)?)))
@@ -221,7 +250,7 @@ impl Fields {
         Fields(fields)
     }
 
-    pub fn from_fields(path: &Path, fields: &HashMap<String, BlockType>) -> Self {
+    pub fn from_fields(path: &Path, fields: &BTreeMap<String, BlockType>) -> Self {
         let fields = fields
             .iter()
             .map(|(name, ty)| {
@@ -262,7 +291,7 @@ impl StructInfo {
     pub fn from_fields(
         path: &Path,
         name: impl Into<String>,
-        fields: &HashMap<String, BlockType>,
+        fields: &BTreeMap<String, BlockType>,
     ) -> Self {
         let name = name.into();
         let mut this_path = path.clone();
@@ -308,7 +337,7 @@ impl FieldInfo {
             } => true,
             Type::List { .. } => false,
         };
-        let type_name = path.type_name() + &name.to_upper_camel_case();
+        let type_name = format_ident!("{}{}", path.type_name(), name.to_upper_camel_case());
         let type_wrapper = match field {
             Type::Single { .. } => Wrapper::Type,
             Type::List { .. } => Wrapper::List,
@@ -316,7 +345,7 @@ impl FieldInfo {
         FieldInfo::builder()
             .path(path.clone())
             .name(name)
-            .type_info(TypeInfo::new(type_wrapper, type_name))
+            .type_info(TypeInfo::new(type_wrapper, quote!(#type_name)))
             .optional(!req)
             .computed(false)
             .description(None)
@@ -325,36 +354,44 @@ impl FieldInfo {
     }
 }
 
-pub fn cargo_simplify_version(constraint: Comparator) -> String {
-    let major = constraint.major;
-    let minor = constraint.minor.unwrap_or(0);
-    let patch = constraint.patch.unwrap_or(0);
-    assert!(
-        constraint.pre.is_empty(),
-        "pre release constraints are not supported"
-    );
-    match constraint.op {
-        Op::Tilde if constraint.minor.is_some() => {
-            format!(">={major}{minor}{patch},<{major}{}.0", minor + 1)
-        }
-        Op::Caret if major == 0 && constraint.minor.is_none() => ">=0.0.0,<1.0.0".to_string(),
-        Op::Caret if major == 0 && minor == 0 && constraint.patch.is_some() => {
-            format!(">=0.0.{patch},<0.0.{}", patch + 1)
-        }
-        Op::Caret if major == 0 => {
-            format!(">=0.{minor}.0,<0.{}.0", minor + 1)
-        }
-        Op::Wildcard if constraint.minor.is_some() => {
-            format!(">={major}.{minor}.0,<{major}.{}.0", minor + 1)
-        }
-        Op::Tilde | Op::Caret | Op::Wildcard => {
-            format!(">={major}.{minor}.{patch},<{}.0.0", major + 1)
-        }
-        Op::Exact => format!("={major}.{minor}.{patch}"),
-        Op::Greater => format!(">{major}.{minor}.{patch}"),
-        Op::GreaterEq => format!(">={major}.{minor}.{patch}"),
-        Op::Less => format!("<{major}.{minor}.{patch}"),
-        Op::LessEq => format!("<={major}.{minor}.{patch}"),
-        _ => unimplemented!(),
+/// Format `tokens` as Rust source file.
+///
+/// # Panics
+///
+/// Will panic if `tokens` are not a valid Rust source file.
+pub fn format_tokens(tokens: TokenStream) -> String {
+    let file =
+        syn::parse2(tokens).unwrap_or_else(|err| panic!("failed to parse generated source: {err}"));
+    prettyplease::unparse(&file)
+}
+
+#[cfg(test)]
+mod tests {
+    use tf_bindgen_schema::provider::v1_0::Block;
+
+    use super::block_paths;
+
+    #[test]
+    fn nested_block_paths() {
+        let block: Block = serde_json::from_str(
+            r#"{
+                "attributes": {
+                    "ports": { "type": ["list", ["object", { "port": "number" }]], "optional": true }
+                },
+                "block_types": {
+                    "spec": {
+                        "nesting_mode": "single",
+                        "block": {
+                            "block_types": {
+                                "container": { "nesting_mode": "list", "block": {} }
+                            }
+                        }
+                    },
+                    "timeouts": { "nesting_mode": "single", "block": {} }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(block_paths(&block), ["spec", "spec.container", "timeouts"]);
     }
 }
@@ -1,9 +1,51 @@
 use heck::ToUpperCamelCase;
-use itertools::Itertools;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
 
 use super::field_info::FieldInfo;
+use super::format_tokens;
 use super::path::Path;
 
+/// Setters used to configure the meta-arguments of providers.
+fn provider_setter() -> TokenStream {
+    quote! {
+        pub fn alias(&mut self, value: impl ::std::convert::Into<::std::string::String>) -> &mut Self {
+            self.__m_alias = Some(value.into());
+            self
+        }
+    }
+}
+
+/// Setters used to configure the meta-arguments of resources and data sources.
+fn meta_setter() -> TokenStream {
+    quote! {
+        pub fn depends_on(&mut self, constructs: &[&dyn ::tf_bindgen::L1Construct]) -> &mut Self {
+            self.__m_meta.depends_on(constructs);
+            self
+        }
+
+        pub fn count(&mut self, value: impl ::tf_bindgen::value::IntoValue<i64>) -> &mut Self {
+            self.__m_meta.count(value);
+            self
+        }
+
+        pub fn for_each(&mut self, value: impl ::tf_bindgen::IntoForEach) -> &mut Self {
+            self.__m_meta.for_each(value);
+            self
+        }
+
+        pub fn provider(&mut self, value: &dyn ::tf_bindgen::Provider) -> &mut Self {
+            self.__m_meta.provider(value);
+            self
+        }
+
+        pub fn lifecycle(&mut self, value: ::tf_bindgen::Lifecycle) -> &mut Self {
+            self.__m_meta.lifecycle(value);
+            self
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum StructType {
     Provider {
@@ -11,9 +53,16 @@ pub enum StructType {
         ver: String,
         nested: Vec<StructInfo>,
     },
-    Construct {
+    Resource {
         ty: String,
         nested: Vec<StructInfo>,
+        /// Paths of the nested blocks (e.g. `spec.container`).
+        blocks: Vec<String>,
+    },
+    DataSource {
+        ty: String,
+        nested: Vec<StructInfo>,
+        blocks: Vec<String>,
     },
     Nested,
 }
@@ -38,333 +87,384 @@ impl StructInfo {
     pub fn ty(&self) -> &str {
         match &self.ty {
             StructType::Provider { ty, .. } => ty,
-            StructType::Construct { ty, .. } => ty,
+            StructType::Resource { ty, .. } | StructType::DataSource { ty, .. } => ty,
             StructType::Nested => unimplemented!(),
         }
     }
 
+    /// Returns the number of structs generated by [`StructInfo::gen_tokens`] (i.e. this struct,
+    /// its nested structs and their builders).
+    pub fn struct_count(&self) -> usize {
+        let nested = match &self.ty {
+            StructType::Provider { nested, .. }
+            | StructType::Resource { nested, .. }
+            | StructType::DataSource { nested, .. } => nested.iter().map(Self::struct_count).sum(),
+            StructType::Nested => 0,
+        };
+        2 + nested
+    }
+
+    /// Generate the Rust source of this struct and all of its nested structs.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the generated code is not valid Rust.
     pub fn gen_rust(&self) -> String {
-        let mut sources = vec![
+        format_tokens(self.gen_tokens())
+    }
+
+    pub fn gen_tokens(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        tokens.extend([
             self.gen_struct(),
             self.gen_struct_impl(),
             self.gen_builder_struct(),
             self.gen_build_impl(),
             self.gen_builder_setter(),
-        ];
+        ]);
         match &self.ty {
-            StructType::Provider { nested, .. } | StructType::Construct { nested, .. } => {
-                sources.extend(nested.iter().map(|nested| nested.gen_rust()))
+            StructType::Provider { nested, .. }
+            | StructType::Resource { nested, .. }
+            | StructType::DataSource { nested, .. } => {
+                tokens.extend(nested.iter().map(StructInfo::gen_tokens))
             }
             StructType::Nested => {}
         }
-        sources.join("\n")
+        tokens
     }
 
-    pub fn gen_struct(&self) -> String {
-        let prefix = self.path.type_name();
-        let name = self.name.to_upper_camel_case();
-        let fields = self.fields.iter().map(FieldInfo::gen_field).join(",\n");
-        match self.ty {
-            StructType::Provider { .. } => format!(
-                r#"#[derive(::std::clone::Clone, ::tf_bindgen::serde::Serialize)]
-				#[serde(crate = "::tf_bindgen::serde")]
-				pub struct {prefix}{name} {{
-					#[serde(skip_serializing)]
-					__m_scope: ::std::rc::Rc<dyn ::tf_bindgen::Scope>, 
-					{fields}
-				}}"#
-            ),
-            StructType::Construct { .. } => format!(
-                r#"#[derive(::std::clone::Clone, ::tf_bindgen::serde::Serialize)]
-				#[serde(crate = "::tf_bindgen::serde")]
-				pub struct {prefix}{name} {{
-					#[serde(skip_serializing)]
-					__m_scope: ::std::rc::Rc<dyn ::tf_bindgen::Scope>, 
-					#[serde(skip_serializing)]
-					__m_name: ::std::string::String,
-					{fields}
-				}}"#
-            ),
-            StructType::Nested => format!(
-                r#"#[derive(::std::clone::Clone, ::tf_bindgen::serde::Serialize)]
-				#[serde(crate = "::tf_bindgen::serde")]
-				pub struct {prefix}{name} {{
-					{fields}
-				}}"#
-            ),
-        }
+    /// Name of the generated struct.
+    fn ident(&self) -> Ident {
+        format_ident!(
+            "{}{}",
+            self.path.type_name(),
+            self.name.to_upper_camel_case()
+        )
     }
 
-    pub fn gen_builder_struct(&self) -> String {
-        let prefix = self.path.type_name();
-        let name = self.name.to_upper_camel_case();
-        let fields = self
-            .fields
+    /// Name of the generated builder struct.
+    fn builder_ident(&self) -> Ident {
+        format_ident!("{}Builder", self.ident())
+    }
+
+    /// Fields which can be set using the builder.
+    fn builder_fields(&self) -> impl Iterator<Item = &FieldInfo> {
+        self.fields
             .iter()
             .filter(|field| !field.is_computed() || field.is_optional())
-            .map(FieldInfo::gen_builder_field)
-            .join(",\n");
-        match self.ty {
-            StructType::Provider { .. } => format!(
-                r#"pub struct {prefix}{name}Builder {{
-					__m_scope: ::std::rc::Rc<dyn ::tf_bindgen::Scope>, 
-					{fields}
-				}}"#
-            ),
-            StructType::Construct { .. } => format!(
-                r#"pub struct {prefix}{name}Builder {{
-					__m_scope: ::std::rc::Rc<dyn ::tf_bindgen::Scope>, 
-					__m_name: ::std::string::String,
-					{fields}
-				}}"#
-            ),
-            StructType::Nested => format!(
-                r#"pub struct {prefix}{name}Builder {{
-					{fields}
-				}}"#
-            ),
+    }
+
+    pub fn gen_struct(&self) -> TokenStream {
+        let name = self.ident();
+        let fields = self.fields.iter().map(FieldInfo::gen_field);
+        let meta_fields = match self.ty {
+            StructType::Provider { .. } => quote! {
+                #[serde(skip_serializing)]
+                __m_scope: ::std::rc::Rc<dyn ::tf_bindgen::Scope>,
+                #[serde(skip_serializing)]
+                __m_alias: ::std::option::Option<::std::string::String>,
+            },
+            StructType::Resource { .. } | StructType::DataSource { .. } => quote! {
+                #[serde(skip_serializing)]
+                __m_scope: ::std::rc::Rc<dyn ::tf_bindgen::Scope>,
+                #[serde(skip_serializing)]
+                __m_name: ::std::string::String,
+                #[serde(skip_serializing)]
+                __m_meta: ::tf_bindgen::MetaArguments,
+            },
+            StructType::Nested => TokenStream::new(),
+        };
+        quote! {
+            #[derive(::std::clone::Clone, ::tf_bindgen::serde::Serialize)]
+            #[serde(crate = "::tf_bindgen::serde")]
+            pub struct #name {
+                #meta_fields
+                #(#fields),*
+            }
         }
     }
 
-    pub fn gen_struct_impl(&self) -> String {
-        let prefix = self.path.type_name();
-        let name = self.name.to_upper_camel_case();
-        let fields = self
-            .fields
-            .iter()
-            .filter(|field| !field.is_computed() || field.is_optional())
+    pub fn gen_builder_struct(&self) -> TokenStream {
+        let name = self.builder_ident();
+        let fields = self.builder_fields().map(FieldInfo::gen_builder_field);
+        let meta_fields = match self.ty {
+            StructType::Provider { .. } => quote! {
+                __m_scope: ::std::rc::Rc<dyn ::tf_bindgen::Scope>,
+                __m_alias: ::std::option::Option<::std::string::String>,
+            },
+            StructType::Resource { .. } | StructType::DataSource { .. } => quote! {
+                __m_scope: ::std::rc::Rc<dyn ::tf_bindgen::Scope>,
+                __m_name: ::std::string::String,
+                __m_meta: ::tf_bindgen::MetaArguments,
+            },
+            StructType::Nested => TokenStream::new(),
+        };
+        quote! {
+            pub struct #name {
+                #meta_fields
+                #(#fields),*
+            }
+        }
+    }
+
+    pub fn gen_struct_impl(&self) -> TokenStream {
+        let name = self.ident();
+        let builder = self.builder_ident();
+        let fields: Vec<_> = self
+            .builder_fields()
             .map(|field| {
-                let name = field.name();
-                format!("{name}: None")
+                let name = field.ident();
+                quote!(#name: None)
             })
-            .join(",\n");
-        let prepare_fields = self
+            .collect();
+        let prepare_fields: Vec<_> = self
             .fields
             .iter()
             .map(|field| {
-                let name = field.name();
-                format!(
-                    r#"{name}: {{
-						let path = format!("{{prefix}}.{name}");
-						self.{name}.prepare(path)
-					}}"#
-                )
+                let name = field.ident();
+                let path = format!("{{prefix}}.{}", field.raw_name());
+                quote! {
+                    #name: {
+                        let path = format!(#path);
+                        self.#name.prepare(path)
+                    }
+                }
             })
-            .join(",\n");
+            .collect();
         match &self.ty {
-            StructType::Provider { ty, .. } => format!(
-                r#"impl {prefix}{name} {{
-					pub fn create<C: ::tf_bindgen::Scope + 'static>(
-						scope: &::std::rc::Rc<C>,
-					) -> {prefix}{name}Builder {{
-						{prefix}{name}Builder {{
-							__m_scope: scope.clone(),
-							{fields}
-						}}
-					}}
-
-				}}
-
-				impl ::tf_bindgen::Scope for {prefix}{name} {{
-					fn stack(&self) -> ::tf_bindgen::Stack {{
-						self.__m_scope.stack()
-					}}
-
-					fn path(&self) -> ::tf_bindgen::Path {{
-						let mut path = self.__m_scope.path();
-						path.push("{ty}");
-						path
-					}}
-				}}"#
-            ),
-            StructType::Construct { .. } => format!(
-                r#"impl {prefix}{name} {{
-					pub fn create<C: ::tf_bindgen::Scope + 'static>(
-						scope: &::std::rc::Rc<C>,
-						name: impl ::std::convert::Into<::std::string::String>
-					) -> {prefix}{name}Builder {{
-						{prefix}{name}Builder {{
-							__m_scope: scope.clone(),
-							__m_name: name.into(),
-							{fields}
-						}}
-					}}
-				}}
-
-				impl ::tf_bindgen::Scope for {prefix}{name} {{
-					fn stack(&self) -> ::tf_bindgen::Stack {{
-						self.__m_scope.stack()
-					}}
-
-					fn path(&self) -> ::tf_bindgen::Path {{
-						let mut path = self.__m_scope.path();
-						path.push(&self.__m_name);
-						path
-					}}
-				}}
- 
-				impl ::tf_bindgen::value::Prepare for {prefix}{name} {{
-					fn prepare(self, prefix: impl Into<::std::string::String>) -> Self {{
-						let prefix = prefix.into();
-						Self {{
-							__m_scope: self.__m_scope,
-							__m_name: self.__m_name,
-							{prepare_fields}
-						}}
-					}}
-				}}"#
-            ),
-            StructType::Nested => format!(
-                r#"impl {prefix}{name} {{
-					pub fn builder() -> {prefix}{name}Builder {{
-						{prefix}{name}Builder {{
-							{fields}
-						}}
-					}}
-				}}
-
-				impl ::tf_bindgen::value::Prepare for {prefix}{name} {{
-					fn prepare(self, prefix: impl Into<::std::string::String>) -> Self {{
-						let prefix = prefix.into();
-						Self {{
-							{prepare_fields}
-						}}
-					}}
-				}}
-
-				impl ::tf_bindgen::value::IntoValue<{prefix}{name}> for {prefix}{name} {{
-					fn into_value(self) -> ::tf_bindgen::Value<{prefix}{name}> {{
-						::tf_bindgen::Value::Value {{ value: ::std::rc::Rc::new(self) }}
-					}}
-				}}
-
-				impl ::tf_bindgen::value::IntoValueList<{prefix}{name}> for {prefix}{name} {{
-					fn into_value_list(self) -> ::std::vec::Vec<::tf_bindgen::Value<{prefix}{name}>> {{
-						use ::tf_bindgen::value::IntoValue;
-						std::vec![self.into_value()]
-					}}
-				}}"#
-            ),
+            StructType::Provider { ty, .. } => quote! {
+                impl #name {
+                    pub fn create<C: ::tf_bindgen::Scope + 'static>(
+                        scope: &::std::rc::Rc<C>,
+                    ) -> #builder {
+                        #builder {
+                            __m_scope: scope.clone(),
+                            __m_alias: None,
+                            #(#fields),*
+                        }
+                    }
+                }
+
+                impl ::tf_bindgen::Scope for #name {
+                    fn stack(&self) -> ::tf_bindgen::Stack {
+                        self.__m_scope.stack()
+                    }
+
+                    fn path(&self) -> ::tf_bindgen::Path {
+                        let mut path = self.__m_scope.path();
+                        path.push(#ty);
+                        path
+                    }
+                }
+            },
+            StructType::Resource { .. } | StructType::DataSource { .. } => quote! {
+                impl #name {
+                    pub fn create<C: ::tf_bindgen::Scope + 'static>(
+                        scope: &::std::rc::Rc<C>,
+                        name: impl ::std::convert::Into<::std::string::String>
+                    ) -> #builder {
+                        #builder {
+                            __m_scope: scope.clone(),
+                            __m_name: name.into(),
+                            __m_meta: ::std::default::Default::default(),
+                            #(#fields),*
+                        }
+                    }
+                }
+
+                impl ::tf_bindgen::Scope for #name {
+                    fn stack(&self) -> ::tf_bindgen::Stack {
+                        self.__m_scope.stack()
+                    }
+
+                    fn path(&self) -> ::tf_bindgen::Path {
+                        let mut path = self.__m_scope.path();
+                        path.push(&self.__m_name);
+                        path
+                    }
+                }
+
+                impl ::tf_bindgen::value::Prepare for #name {
+                    fn prepare(self, prefix: impl Into<::std::string::String>) -> Self {
+                        let prefix = prefix.into();
+                        Self {
+                            __m_scope: self.__m_scope,
+                            __m_name: self.__m_name,
+                            __m_meta: self.__m_meta,
+                            #(#prepare_fields),*
+                        }
+                    }
+                }
+            },
+            StructType::Nested => quote! {
+                impl #name {
+                    pub fn builder() -> #builder {
+                        #builder {
+                            #(#fields),*
+                        }
+                    }
+                }
+
+                impl ::tf_bindgen::value::Prepare for #name {
+                    fn prepare(self, prefix: impl Into<::std::string::String>) -> Self {
+                        let prefix = prefix.into();
+                        Self {
+                            #(#prepare_fields),*
+                        }
+                    }
+                }
+
+                impl ::tf_bindgen::value::IntoValue<#name> for #name {
+                    fn into_value(self) -> ::tf_bindgen::Value<#name> {
+                        ::tf_bindgen::Value::Value { value: ::std::rc::Rc::new(self) }
+                    }
+                }
+
+                impl ::tf_bindgen::value::IntoValueList<#name> for #name {
+                    fn into_value_list(self) -> ::std::vec::Vec<::tf_bindgen::Value<#name>> {
+                        use ::tf_bindgen::value::IntoValue;
+                        std::vec![self.into_value()]
+                    }
+                }
+            },
         }
     }
 
-    pub fn gen_builder_setter(&self) -> String {
-        let prefix = self.path.type_name();
-        let name = self.name.to_upper_camel_case();
-        let setter = self
-            .fields
-            .iter()
-            .filter(|field| !field.is_computed() || field.is_optional())
-            .map(FieldInfo::builder_setter_impl)
-            .join("\n");
-        format!(
-            r#"impl {prefix}{name}Builder {{
-				{setter}
-			}}"#
-        )
+    pub fn gen_builder_setter(&self) -> TokenStream {
+        let builder = self.builder_ident();
+        let setter = self.builder_fields().map(FieldInfo::builder_setter_impl);
+        let meta_setter = match self.ty {
+            StructType::Provider { .. } => provider_setter(),
+            StructType::Resource { .. } | StructType::DataSource { .. } => meta_setter(),
+            StructType::Nested => TokenStream::new(),
+        };
+        quote! {
+            impl #builder {
+                #(#setter)*
+                #meta_setter
+            }
+        }
     }
 
-    pub fn gen_build_impl(&self) -> String {
-        let prefix = self.path.type_name();
-        let name = self.name.to_upper_camel_case();
-        let assign = self
+    pub fn gen_build_impl(&self) -> TokenStream {
+        let name = self.ident();
+        let builder = self.builder_ident();
+        let assign: Vec<_> = self
             .fields
             .iter()
-            // .filter(|field| !field.is_computed() || field.is_optional())
             .map(|field| {
+                let ident = field.ident();
                 let name = field.name();
                 if field.is_optional() {
-                    format!(r#"{name}: ::tf_bindgen::value::Cell::new("{name}", self.{name}.clone())"#)
+                    quote!(#ident: ::tf_bindgen::value::Cell::new(#name, self.#ident.clone()))
                 } else if field.is_computed() {
-					format!(r#"{name}: ::tf_bindgen::value::Cell::new("{name}", tf_bindgen::value::Computed::default())"#)
-				} else {
-                    format!(r#"{name}: ::tf_bindgen::value::Cell::new("{name}", self.{name}.clone().expect("field `{name}`"))"#)
+                    quote!(#ident: ::tf_bindgen::value::Cell::new(#name, tf_bindgen::value::Computed::default()))
+                } else {
+                    let expect = format!("field `{name}`");
+                    quote!(#ident: ::tf_bindgen::value::Cell::new(#name, self.#ident.clone().expect(#expect)))
                 }
             })
-            .join(",\n");
-        let config: String = self
-            .fields
-            .iter()
-            .filter(|field| !field.is_computed() || field.is_optional())
+            .collect();
+        let config: TokenStream = self
+            .builder_fields()
             .map(|field| {
-                let name = field.name();
+                let name = field.ident();
                 let raw_name = field.raw_name();
-                format!(
-                    r#"
-						let value = ::tf_bindgen::json::to_value(&self.{name}).unwrap();
-						config.insert("{raw_name}".to_string(), value);
-					"#
-                )
+                quote! {
+                    let value = ::tf_bindgen::json::to_value(&self.#name).unwrap();
+                    config.insert(#raw_name.to_string(), value);
+                }
             })
             .collect();
         match &self.ty {
-            StructType::Provider { ver, .. } => format!(
-                r#"impl ::tf_bindgen::Provider for {prefix}{name} {{
-					fn to_schema(&self) -> (::std::string::String, ::tf_bindgen::schema::document::Provider) {{
-						let mut config = ::tf_bindgen::schema::document::Provider::new();
-						{config}
-						("{ver}".to_string(), config)
-					}}
-				}}
-
-				impl {prefix}{name}Builder {{
-					pub fn build(&mut self) -> ::std::rc::Rc<{prefix}{name}> {{
-						use tf_bindgen::Scope;
-						let this = ::std::rc::Rc::new({prefix}{name} {{
-							__m_scope: self.__m_scope.clone(),
-							{assign}
-						}});
-						this.stack().add_provider(this.clone());
-						this
-					}}
-				}}"#
-            ),
-            StructType::Construct { ty, .. } => format!(
-                r#"impl ::tf_bindgen::L1Construct for {prefix}{name} {{
-					fn to_schema(&self) -> (::std::string::String, ::tf_bindgen::schema::document::Resource) {{
-						use tf_bindgen::Scope;
-						let mut config = ::std::collections::HashMap::new();
-						{config}
-						let path = self.path();
-						let resource = ::tf_bindgen::schema::document::Resource {{
-							meta: ::tf_bindgen::schema::document::ResourceMeta {{
-								metadata: ::tf_bindgen::schema::document::ResourceMetadata {{
-									path: path.to_string(),
-									unique_id: path.name().to_string(),
-								}},
-							}},
-							config
-						}};
-						("{ty}".to_string(), resource)
-					}}
-				}}
-
-
-				impl {prefix}{name}Builder {{
-					pub fn build(&mut self) -> ::std::rc::Rc<{prefix}{name}> {{
-						use tf_bindgen::Scope;
-						use ::tf_bindgen::value::Prepare;
-						let this = {prefix}{name} {{
-							__m_scope: self.__m_scope.clone(),
-							__m_name: self.__m_name.clone(),
-							{assign}
-						}};
-						let path = format!("{ty}.{{}}", this.path().id());
-						let this = ::std::rc::Rc::new(this.prepare(path));
-						this.stack().add_resource(this.clone());
-						this
-					}}
-				}}"#
-            ),
-            StructType::Nested => format!(
-                r#"impl {prefix}{name}Builder {{
-					pub fn build(&mut self) -> {prefix}{name} {{
-						{prefix}{name} {{
-							{assign}
-						}}
-					}}
-				}}"#
-            ),
+            StructType::Provider { ver, .. } => quote! {
+                impl ::tf_bindgen::Provider for #name {
+                    fn to_schema(&self) -> (::std::string::String, ::tf_bindgen::schema::document::Provider) {
+                        let mut config = ::tf_bindgen::schema::document::Provider::new();
+                        #config
+                        (#ver.to_string(), config)
+                    }
+
+                    fn alias(&self) -> ::std::option::Option<&str> {
+                        self.__m_alias.as_deref()
+                    }
+                }
+
+                impl #builder {
+                    pub fn build(&mut self) -> ::std::rc::Rc<#name> {
+                        use tf_bindgen::Scope;
+                        let this = ::std::rc::Rc::new(#name {
+                            __m_scope: self.__m_scope.clone(),
+                            __m_alias: self.__m_alias.clone(),
+                            #(#assign),*
+                        });
+                        this.stack().add_provider(this.clone());
+                        this
+                    }
+                }
+            },
+            StructType::Resource { ty, blocks, .. } | StructType::DataSource { ty, blocks, .. } => {
+                let (reference, register) = match &self.ty {
+                    StructType::DataSource { .. } => {
+                        (format!("data.{ty}.{{}}"), format_ident!("add_data_source"))
+                    }
+                    _ => (format!("{ty}.{{}}"), format_ident!("add_resource")),
+                };
+                quote! {
+                    impl ::tf_bindgen::L1Construct for #name {
+                        fn to_schema(&self) -> (::std::string::String, ::tf_bindgen::schema::document::Resource) {
+                            use tf_bindgen::Scope;
+                            let mut config = ::std::collections::BTreeMap::new();
+                            #config
+                            self.__m_meta.extend_config(&mut config);
+                            let path = self.path();
+                            let resource = ::tf_bindgen::schema::document::Resource {
+                                meta: ::tf_bindgen::schema::document::ResourceMeta {
+                                    metadata: ::tf_bindgen::schema::document::ResourceMetadata {
+                                        path: path.to_string(),
+                                        unique_id: path.name().to_string(),
+                                        blocks: ::std::option::Option::Some(::std::vec![#(#blocks.to_string()),*]),
+                                    },
+                                },
+                                config
+                            };
+                            (#ty.to_string(), resource)
+                        }
+
+                        fn reference(&self) -> ::std::string::String {
+                            use tf_bindgen::Scope;
+                            format!(#reference, self.path().id())
+                        }
+                    }
+
+                    impl #builder {
+                        pub fn build(&mut self) -> ::std::rc::Rc<#name> {
+                            use tf_bindgen::Scope;
+                            use ::tf_bindgen::value::Prepare;
+                            let this = #name {
+                                __m_scope: self.__m_scope.clone(),
+                                __m_name: self.__m_name.clone(),
+                                __m_meta: self.__m_meta.clone(),
+                                #(#assign),*
+                            };
+                            let reference = ::tf_bindgen::L1Construct::reference(&this);
+                            let path = this.stack().attribute_prefix(&reference);
+                            let this = ::std::rc::Rc::new(this.prepare(path));
+                            this.stack().#register(this.clone());
+                            this
+                        }
+                    }
+                }
+            }
+            StructType::Nested => quote! {
+                impl #builder {
+                    pub fn build(&mut self) -> #name {
+                        #name {
+                            #(#assign),*
+                        }
+                    }
+                }
+            },
         }
     }
 }
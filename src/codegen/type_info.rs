@@ -1,4 +1,6 @@
 use heck::ToUpperCamelCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
 use tf_bindgen_schema::provider::v1_0::BlockType;
 
 use super::path::Path;
@@ -14,23 +16,23 @@ pub enum Wrapper {
 #[derive(Debug, Clone)]
 pub struct TypeInfo {
     wrapper: Wrapper,
-    name: String,
+    name: TokenStream,
 }
 
 impl TypeInfo {
     pub fn from_schema(path: &Path, name: &str, schema: &BlockType) -> Self {
-        let composed_type = path.type_name() + &name.to_upper_camel_case();
+        let composed_type = format_ident!("{}{}", path.type_name(), name.to_upper_camel_case());
         let type_name = match schema {
             BlockType::Set(inner) | BlockType::Map(inner) | BlockType::List(inner) => match **inner
             {
                 BlockType::Set(_) | BlockType::Map(_) | BlockType::List(_) => {
                     unimplemented!("double nested types are not supported")
                 }
-                BlockType::Object(_) => &composed_type,
-                _ => base_type_to_string(inner),
+                BlockType::Object(_) => quote!(#composed_type),
+                _ => base_type(inner),
             },
-            BlockType::Object(_) => &composed_type,
-            _ => base_type_to_string(schema),
+            BlockType::Object(_) => quote!(#composed_type),
+            _ => base_type(schema),
         };
         let wrapper = match schema {
             BlockType::Set(_) => Wrapper::Set,
@@ -40,15 +42,12 @@ impl TypeInfo {
         };
         Self {
             wrapper,
-            name: type_name.to_string(),
+            name: type_name,
         }
     }
 
-    pub fn new(wrapper: Wrapper, name: impl Into<String>) -> Self {
-        Self {
-            wrapper,
-            name: name.into(),
-        }
+    pub fn new(wrapper: Wrapper, name: TokenStream) -> Self {
+        Self { wrapper, name }
     }
 
     pub fn wrapper(&self) -> &Wrapper {
@@ -56,31 +55,31 @@ impl TypeInfo {
     }
 
     /// Returns the unwrapped type.
-    pub fn type_name(&self) -> &str {
+    pub fn type_name(&self) -> &TokenStream {
         &self.name
     }
 
     /// Returns the composed type.
-    pub fn source(&self) -> String {
-        let type_name = &self.name;
-        let type_name = format!("::tf_bindgen::Value<{type_name}>");
+    pub fn source(&self) -> TokenStream {
+        let inner = &self.name;
+        let type_name = quote!(::tf_bindgen::Value<#inner>);
         match self.wrapper {
-            Wrapper::List => format!("::std::vec::Vec<{type_name}>"),
+            Wrapper::List => quote!(::std::vec::Vec<#type_name>),
             Wrapper::Map => {
-                format!("::std::collections::HashMap<::std::string::String, {type_name}>")
+                quote!(::std::collections::HashMap<::std::string::String, #type_name>)
             }
             Wrapper::Type => type_name,
-            Wrapper::Set => format!("::std::collections::HashSet<{type_name}>"),
+            Wrapper::Set => quote!(::tf_bindgen::value::ValueSet<#inner>),
         }
     }
 }
 
-fn base_type_to_string(schema: &BlockType) -> &str {
+fn base_type(schema: &BlockType) -> TokenStream {
     match schema {
-        BlockType::String => "::std::string::String",
-        BlockType::Bool => "bool",
-        BlockType::Number => "i64",
-        BlockType::Dynamic => "::tf_bindgen::json::Value",
+        BlockType::String => quote!(::std::string::String),
+        BlockType::Bool => quote!(bool),
+        BlockType::Number => quote!(i64),
+        BlockType::Dynamic => quote!(::tf_bindgen::json::Value),
         _ => unimplemented!(),
     }
 }
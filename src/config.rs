@@ -1,14 +1,72 @@
-use anyhow::{Context, Result};
-use semver::VersionReq;
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 use toml::{map::Map, Value};
 
+use crate::filter::Filter;
+use crate::version::VersionConstraint;
+
 #[derive(Deserialize, Serialize)]
 pub struct Config {
     pub provider: Map<String, Value>,
 }
 
+/// Used to store the configuration of a single provider.
+///
+/// # Config
+///
+/// A provider can either be specified using a version constraint or a table. Version
+/// constraints may use Cargo's or Terraform's syntax (see [`VersionConstraint::parse`]):
+///
+/// ```toml
+/// [provider]
+/// "hashicorp/kubernetes" = "=2.17.0"
+/// "hashicorp/google" = "~> 4.60"
+///
+/// [provider.docker]
+/// source = "kreuzwerker/docker"
+/// version = "3.0"
+/// hostname = "registry.example.com" # optional
+/// module_name = "docker_provider"   # optional
+/// allow = ["docker_*"]              # optional
+/// deny = ["docker_*_v1"]            # optional
+///
+/// [provider.docker.rename]          # optional
+/// docker_container = "container"
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProviderConfig {
+    /// Name of the provider as specified in the configuration file.
+    pub name: String,
+    /// Source address of the provider (e.g. `hashicorp/kubernetes`).
+    pub source: String,
+    pub version: VersionConstraint,
+    /// Name of the generated Rust module. Defaults to the local name of the provider.
+    pub module_name: Option<String>,
+    /// Used to rename generated resources and data sources. Maps the Terraform type (e.g.
+    /// `docker_container`) to the name used for the Rust module and struct.
+    pub rename: HashMap<String, String>,
+    /// Used to select the resources and data sources to generate.
+    pub filter: Filter,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProviderTable {
+    source: Option<String>,
+    version: String,
+    hostname: Option<String>,
+    module_name: Option<String>,
+    #[serde(default)]
+    rename: HashMap<String, String>,
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
 impl Config {
     /// Load configuration file from file system.
     pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
@@ -16,24 +74,149 @@ impl Config {
         toml::from_str(&config).context("failed to parse config file")
     }
 
-    /// Generates a list of `(<provider name>, <version constraint>)` pairs from specified providers.
+    /// Generates a list of provider configurations from specified providers.
     ///
     /// # Errors
     ///
-    /// Will return `Err` if a provider constraint cannot been parsed.
-    pub fn providers(&self) -> Result<Vec<(String, VersionReq)>> {
+    /// Will return `Err` if a provider constraint cannot been parsed or a provider table is
+    /// invalid.
+    pub fn providers(&self) -> Result<Vec<ProviderConfig>> {
         self.provider
             .iter()
             .map(|(name, provider)| match provider {
                 Value::String(constraint) => {
-                    let version = VersionReq::parse(constraint)
-                        .context("failed to parse version constraint")?;
-                    Ok((name.clone(), version))
+                    let version = VersionConstraint::parse(constraint)
+                        .with_context(|| format!("provider `{name}`"))?;
+                    Ok(ProviderConfig::new(name, version))
+                }
+                Value::Table(table) => {
+                    let table: ProviderTable = Value::Table(table.clone())
+                        .try_into()
+                        .with_context(|| format!("invalid configuration of provider `{name}`"))?;
+                    let version = VersionConstraint::parse(&table.version)
+                        .with_context(|| format!("provider `{name}`"))?;
+                    let mut config = ProviderConfig::new(name, version);
+                    if let Some(source) = table.source {
+                        config.source = source;
+                    }
+                    if let Some(hostname) = table.hostname {
+                        if config.source.split('/').count() != 2 {
+                            bail!("provider `{name}`: `hostname` requires a source of the form `<namespace>/<type>`")
+                        }
+                        config.source = format!("{hostname}/{}", config.source);
+                    }
+                    config.module_name = table.module_name;
+                    config.rename = table.rename;
+                    for pattern in table.allow {
+                        config.filter.allow(pattern);
+                    }
+                    for pattern in table.deny {
+                        config.filter.deny(pattern);
+                    }
+                    Ok(config)
                 }
                 _ => Err(anyhow::anyhow!(
-                    "unexpected type of constraint `{name}` (expected: string)"
+                    "unexpected type of constraint `{name}` (expected: string or table)"
                 )),
             })
             .collect()
     }
 }
+
+impl ProviderConfig {
+    /// Create a new provider configuration. The source address is derived from `name`, i.e.
+    /// `kubernetes` will use `hashicorp/kubernetes` as source.
+    pub fn new(name: impl Into<String>, version: VersionConstraint) -> Self {
+        let name = name.into();
+        let source = if name.contains('/') {
+            name.clone()
+        } else {
+            format!("hashicorp/{name}")
+        };
+        Self {
+            name,
+            source,
+            version,
+            module_name: None,
+            rename: HashMap::new(),
+            filter: Filter::default(),
+        }
+    }
+
+    /// Returns the fully qualified source address (e.g.
+    /// `registry.terraform.io/hashicorp/kubernetes`).
+    pub fn qualified_source(&self) -> String {
+        if self.source.split('/').count() == 2 {
+            format!("registry.terraform.io/{}", self.source)
+        } else {
+            self.source.clone()
+        }
+        .to_lowercase()
+    }
+
+    /// Returns the local name used by Terraform to reference this provider (e.g.
+    /// `kubernetes`).
+    pub fn local_name(&self) -> &str {
+        self.source.split('/').next_back().unwrap()
+    }
+
+    /// Returns the name of the generated Rust module.
+    pub fn module_name(&self) -> &str {
+        self.module_name.as_deref().unwrap_or(self.local_name())
+    }
+
+    /// Returns `true` if `url` is the source address of this provider.
+    pub fn matches(&self, url: &str) -> bool {
+        self.qualified_source() == url.to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn provider_table() {
+        let config: Config = toml::from_str(
+            r#"
+            [provider]
+            kubernetes = "2.17"
+            google = "~> 4.60, != 4.61.0"
+
+            [provider.docker]
+            source = "acme/docker"
+            version = "=3.0.2"
+            hostname = "registry.example.com"
+            module_name = "docker_provider"
+            rename = { docker_container = "container" }
+            deny = ["docker_*_v1"]
+            "#,
+        )
+        .unwrap();
+        let providers = config.providers().unwrap();
+        let docker = providers.iter().find(|p| p.name == "docker").unwrap();
+        assert!(docker.matches("registry.example.com/acme/docker"));
+        assert_eq!(docker.local_name(), "docker");
+        assert_eq!(docker.module_name(), "docker_provider");
+        assert_eq!(docker.rename["docker_container"], "container");
+        assert!(!docker.filter.is_match("docker_image_v1"));
+        let kubernetes = providers.iter().find(|p| p.name == "kubernetes").unwrap();
+        assert!(kubernetes.matches("registry.terraform.io/hashicorp/kubernetes"));
+        assert_eq!(kubernetes.version.to_terraform(), ">= 2.17.0, < 3.0.0");
+        let google = providers.iter().find(|p| p.name == "google").unwrap();
+        assert_eq!(google.version.to_terraform(), "~> 4.60, != 4.61.0");
+    }
+
+    #[test]
+    fn provider_table_unknown_field() {
+        let config: Config = toml::from_str(
+            r#"
+            [provider.docker]
+            version = "3.0"
+            versions = "3.0"
+            "#,
+        )
+        .unwrap();
+        assert!(config.providers().is_err());
+    }
+}
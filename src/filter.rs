@@ -0,0 +1,116 @@
+/// Used to select the resources and data sources to generate bindings for. Patterns are
+/// matched against the Terraform type (e.g. `kubernetes_pod`) and may contain `*` (any
+/// sequence of characters) and `?` (any single character).
+///
+/// A construct is generated if it matches any allow pattern (or no allow pattern was
+/// specified) and does not match any deny pattern.
+#[derive(Clone, Debug, Default)]
+pub struct Filter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl Filter {
+    pub fn allow(&mut self, pattern: impl Into<String>) -> &mut Self {
+        self.allow.push(pattern.into());
+        self
+    }
+
+    pub fn deny(&mut self, pattern: impl Into<String>) -> &mut Self {
+        self.deny.push(pattern.into());
+        self
+    }
+
+    /// Add the patterns of `defaults` (e.g. passed to [`crate::Builder`]) to the filter of a
+    /// provider with the resource and data source types `types`. Allow patterns are only added
+    /// if this filter has no allow patterns of its own and at least one of `types` matches
+    /// them. Thus, allow patterns targeting another provider will not remove all types of this
+    /// provider.
+    pub fn inherit<'a>(&mut self, defaults: &Filter, types: impl IntoIterator<Item = &'a str>) {
+        let targeted = types
+            .into_iter()
+            .any(|ty| defaults.allow.iter().any(|pattern| glob_match(pattern, ty)));
+        if self.allow.is_empty() && targeted {
+            self.allow.extend(defaults.allow.iter().cloned());
+        }
+        self.deny.extend(defaults.deny.iter().cloned());
+    }
+
+    /// Returns `true` if bindings should be generated for `ty`.
+    pub fn is_match(&self, ty: &str) -> bool {
+        let allowed =
+            self.allow.is_empty() || self.allow.iter().any(|pattern| glob_match(pattern, ty));
+        allowed && !self.deny.iter().any(|pattern| glob_match(pattern, ty))
+    }
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<_> = pattern.chars().collect();
+    let text: Vec<_> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was matched at.
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_match, Filter};
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("kubernetes_*", "kubernetes_pod"));
+        assert!(glob_match("*_pod", "kubernetes_pod"));
+        assert!(glob_match("kubernetes_po?", "kubernetes_pod"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("kubernetes_*_v1", "kubernetes_pod"));
+        assert!(!glob_match("kubernetes_pod", "kubernetes_pod_v1"));
+    }
+
+    #[test]
+    fn allow_deny() {
+        let mut filter = Filter::default();
+        filter.allow("kubernetes_pod*").deny("*_v1");
+        assert!(filter.is_match("kubernetes_pod"));
+        assert!(!filter.is_match("kubernetes_pod_v1"));
+        assert!(!filter.is_match("kubernetes_namespace"));
+    }
+
+    #[test]
+    fn inherit() {
+        let mut defaults = Filter::default();
+        defaults.allow("kubernetes_pod").deny("*_v1");
+        let mut kubernetes = Filter::default();
+        kubernetes.inherit(&defaults, ["kubernetes_pod", "kubernetes_service"]);
+        assert!(!kubernetes.is_match("kubernetes_service"));
+        let mut docker = Filter::default();
+        docker.inherit(&defaults, ["docker_image", "docker_image_v1"]);
+        assert!(docker.is_match("docker_image"));
+        assert!(!docker.is_match("docker_image_v1"));
+        let mut own = Filter::default();
+        own.allow("kubernetes_service");
+        own.inherit(&defaults, ["kubernetes_pod", "kubernetes_service"]);
+        assert!(own.is_match("kubernetes_service"));
+        assert!(!own.is_match("kubernetes_pod"));
+    }
+}
@@ -0,0 +1,867 @@
+//! Parser of Terraform's native syntax. Only the structure of a configuration and simple
+//! expressions (literals, templates, references, tuples and objects) are parsed. Every other
+//! expression (e.g. function calls, operations or `for` expressions) is checked for syntax
+//! errors and stored as written (see [`Expression::Raw`]).
+
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+
+use super::{Block, Body, Expression, Item, Part, Step};
+
+/// Parse a configuration written in Terraform's native syntax.
+pub(super) fn parse(source: &str) -> Result<Body> {
+    Parser::new(source).body(false)
+}
+
+/// Parse the content of a template without processing escape sequences (e.g. the content of a
+/// heredoc or a string of a JSON configuration).
+pub(super) fn parse_template(source: &str) -> Result<Vec<Part>> {
+    Parser::new(source).template(false)
+}
+
+/// Parse a single expression (e.g. a reference of a JSON configuration).
+pub(super) fn parse_expression(source: &str) -> Result<Expression> {
+    let mut parser = Parser::new(source);
+    let expression = parser.expression(Context::Interpolation)?;
+    match parser.peek() {
+        Some(c) => Err(parser.error(format!("expected end of expression, found `{c}`"))),
+        None => Ok(expression),
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+/// Context of an expression, which determines the characters terminating it.
+#[derive(Clone, Copy, PartialEq)]
+enum Context {
+    /// Value of an attribute (terminated by a new line or the end of the block).
+    Attribute,
+    /// Element of a tuple (terminated by `,` or `]`).
+    Element,
+    /// Value of an object (terminated by a new line, `,` or `}`).
+    Field,
+    /// Content of an interpolation (terminated by `}` or `~}`).
+    Interpolation,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self { source, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn error(&self, message: impl Display) -> anyhow::Error {
+        let before = &self.source[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        anyhow!("{line}:{column}: {message}")
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        match self.peek() {
+            Some(next) if next == c => {
+                self.bump();
+                Ok(())
+            }
+            Some(next) => Err(self.error(format!("expected `{c}`, found `{next}`"))),
+            None => Err(self.error(format!("expected `{c}`, found end of file"))),
+        }
+    }
+
+    /// Skip whitespace and comments. New lines are skipped only if `newlines` is set.
+    fn skip_space(&mut self, newlines: bool) {
+        loop {
+            let rest = self.rest();
+            if rest.starts_with('#') || rest.starts_with("//") {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                self.pos += end;
+            } else if rest.starts_with("/*") {
+                let end = rest.find("*/").map_or(rest.len(), |i| i + 2);
+                self.pos += end;
+            } else {
+                match self.peek() {
+                    Some(' ' | '\t' | '\r') => self.pos += 1,
+                    Some('\n') if newlines => self.pos += 1,
+                    _ => return,
+                }
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String> {
+        let start = self.pos;
+        match self.peek() {
+            Some(c) if c.is_alphabetic() || c == '_' => {}
+            Some(c) => return Err(self.error(format!("expected identifier, found `{c}`"))),
+            None => return Err(self.error("expected identifier, found end of file")),
+        }
+        while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_' || c == '-') {
+            self.bump();
+        }
+        Ok(self.source[start..self.pos].to_string())
+    }
+
+    fn body(&mut self, nested: bool) -> Result<Body> {
+        let mut body = Body::default();
+        loop {
+            self.skip_space(true);
+            match self.peek() {
+                None if nested => return Err(self.error("expected `}`, found end of file")),
+                None => return Ok(body),
+                Some('}') if nested => {
+                    self.bump();
+                    return Ok(body);
+                }
+                _ => {}
+            }
+            let name = self.identifier()?;
+            self.skip_space(false);
+            if self.peek() == Some('=') {
+                self.bump();
+                let value = self.expression(Context::Attribute)?;
+                self.skip_space(false);
+                match self.peek() {
+                    None | Some('\n' | '}') => {}
+                    Some(c) => return Err(self.error(format!("expected new line, found `{c}`"))),
+                }
+                body.items.push(Item::Attribute(name, value));
+                continue;
+            }
+            let mut labels = Vec::new();
+            loop {
+                self.skip_space(false);
+                match self.peek() {
+                    Some('{') => break,
+                    Some('"') => {
+                        self.bump();
+                        let label = Expression::Template(self.template(true)?);
+                        let label = label
+                            .as_literal()
+                            .ok_or_else(|| self.error("block labels must not be templates"))?;
+                        labels.push(label);
+                    }
+                    _ => labels.push(self.identifier()?),
+                }
+            }
+            self.bump();
+            let block = Block {
+                ty: name,
+                labels,
+                body: self.body(true)?,
+            };
+            body.items.push(Item::Block(block));
+        }
+    }
+
+    /// Parse an expression. Expressions not supported by [`Parser::term`] are checked using
+    /// [`Parser::skip_expression`] and stored as written.
+    fn expression(&mut self, context: Context) -> Result<Expression> {
+        let multiline = matches!(context, Context::Element | Context::Interpolation);
+        self.skip_space(multiline);
+        let start = self.pos;
+        let term = self.term().ok().flatten();
+        self.skip_space(multiline);
+        if let Some(term) = term {
+            if self.at_end(context) {
+                return Ok(term);
+            }
+        }
+        self.pos = start;
+        self.skip_expression(multiline)?;
+        self.skip_space(multiline);
+        if !self.at_end(context) {
+            let expected = match context {
+                Context::Attribute => "new line",
+                Context::Element => "`,` or `]`",
+                Context::Field => "new line, `,` or `}`",
+                Context::Interpolation => "`}`",
+            };
+            let found = self.peek().map(|c| format!("`{c}`")).unwrap_or_default();
+            return Err(self.error(format!("expected {expected}, found {found}")));
+        }
+        let raw = self.source[start..self.pos].trim();
+        Ok(Expression::Raw(raw.to_string()))
+    }
+
+    fn at_end(&self, context: Context) -> bool {
+        matches!(
+            (context, self.peek()),
+            (_, None)
+                | (Context::Attribute, Some('\n' | '}'))
+                | (Context::Element, Some(',' | ']'))
+                | (Context::Field, Some('\n' | ',' | '}'))
+                | (Context::Interpolation, Some('}'))
+        ) || (context == Context::Interpolation && self.rest().starts_with("~}"))
+    }
+
+    /// Parse literals, templates, references, tuples and objects. Returns `None` if the
+    /// expression is not one of these.
+    fn term(&mut self) -> Result<Option<Expression>> {
+        let expression = match self.peek() {
+            Some('"') => {
+                self.bump();
+                Expression::Template(self.template(true)?)
+            }
+            Some('<') if self.rest().starts_with("<<") => self.heredoc()?,
+            Some('[') => {
+                self.bump();
+                self.skip_space(true);
+                if self.rest().starts_with("for ") {
+                    return Ok(None);
+                }
+                let mut items = Vec::new();
+                loop {
+                    self.skip_space(true);
+                    if self.peek() == Some(']') {
+                        self.bump();
+                        break;
+                    }
+                    items.push(self.expression(Context::Element)?);
+                    if self.peek() == Some(',') {
+                        self.bump();
+                    }
+                }
+                Expression::Tuple(items)
+            }
+            Some('{') => {
+                self.bump();
+                self.skip_space(true);
+                if self.rest().starts_with("for ") {
+                    return Ok(None);
+                }
+                let mut fields = Vec::new();
+                loop {
+                    self.skip_space(true);
+                    let key = match self.peek() {
+                        Some('}') => {
+                            self.bump();
+                            break;
+                        }
+                        Some('"') => {
+                            self.bump();
+                            match Expression::Template(self.template(true)?).as_literal() {
+                                Some(key) => key,
+                                None => return Ok(None),
+                            }
+                        }
+                        Some(c) if c.is_alphabetic() || c == '_' => self.identifier()?,
+                        _ => return Ok(None),
+                    };
+                    self.skip_space(false);
+                    match self.bump() {
+                        Some('=' | ':') => {}
+                        _ => return Ok(None),
+                    }
+                    fields.push((key, self.expression(Context::Field)?));
+                    if self.peek() == Some(',') {
+                        self.bump();
+                    }
+                }
+                Expression::Object(fields)
+            }
+            Some(c) if c.is_ascii_digit() => Expression::Number(self.number()?),
+            Some('-') if self.rest()[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                self.bump();
+                Expression::Number(format!("-{}", self.number()?))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.identifier()?;
+                match name.as_str() {
+                    "true" => Expression::Bool(true),
+                    "false" => Expression::Bool(false),
+                    "null" => Expression::Null,
+                    _ if self.peek() == Some('(') => return Ok(None),
+                    _ => match self.steps()? {
+                        Some(steps) => Expression::Traversal(name, steps),
+                        None => return Ok(None),
+                    },
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(expression))
+    }
+
+    fn number(&mut self) -> Result<String> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.bump();
+        }
+        let rest = self.rest();
+        if rest.starts_with('.') && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            self.bump();
+            while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                self.bump();
+            }
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.bump();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.bump();
+            }
+            if !matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                return Err(self.error("expected digits of exponent"));
+            }
+            while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                self.bump();
+            }
+        }
+        Ok(self.source[start..self.pos].to_string())
+    }
+
+    /// Parse the steps of a traversal (e.g. `.metadata[0].name`).
+    fn steps(&mut self) -> Result<Option<Vec<Step>>> {
+        let mut steps = Vec::new();
+        loop {
+            match self.peek() {
+                Some('.') => {
+                    self.bump();
+                    match self.peek() {
+                        Some('*') => {
+                            self.bump();
+                            steps.push(Step::Splat);
+                        }
+                        Some(c) if c.is_ascii_digit() => {
+                            steps.push(Step::Index(Expression::Number(self.number()?)))
+                        }
+                        _ => steps.push(Step::Attribute(self.identifier()?)),
+                    }
+                }
+                Some('[') => {
+                    self.bump();
+                    self.skip_space(true);
+                    if self.peek() == Some('*') {
+                        self.bump();
+                        self.skip_space(true);
+                        self.expect(']')?;
+                        steps.push(Step::Splat);
+                        continue;
+                    }
+                    let index = self.expression(Context::Element)?;
+                    if self.peek() != Some(']') {
+                        return Ok(None);
+                    }
+                    self.bump();
+                    steps.push(Step::Index(index));
+                }
+                _ => return Ok(Some(steps)),
+            }
+        }
+    }
+
+    /// Parse a template. If `quoted` is set, the template is terminated by `"` and escape
+    /// sequences are processed. Otherwise the template ends at the end of the source.
+    fn template(&mut self, quoted: bool) -> Result<Vec<Part>> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("$${") || rest.starts_with("%%{") {
+                literal += &rest[1..3];
+                self.pos += 3;
+                continue;
+            }
+            if rest.starts_with("${") || rest.starts_with("%{") {
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                let start = self.pos;
+                self.pos += 2;
+                if rest.starts_with('%') {
+                    self.skip_nested()?;
+                    let directive = &self.source[start..self.pos];
+                    parts.push(Part::Directive(directive.to_string()));
+                    continue;
+                }
+                if self.peek() == Some('~') {
+                    self.bump();
+                }
+                let expression = self.expression(Context::Interpolation)?;
+                if self.peek() == Some('~') {
+                    self.bump();
+                }
+                self.expect('}')?;
+                parts.push(Part::Interpolation(expression));
+                continue;
+            }
+            match self.peek() {
+                None | Some('\n') if quoted => return Err(self.error("unterminated string")),
+                None => break,
+                _ => {}
+            }
+            match self.bump() {
+                Some('"') if quoted => break,
+                Some('\\') if quoted => literal.push(self.escape()?),
+                Some(c) => literal.push(c),
+                None => unreachable!(),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(parts)
+    }
+
+    fn escape(&mut self) -> Result<char> {
+        let c = match self.bump() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some(c @ ('u' | 'U')) => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let code = self.rest().get(..len).unwrap_or_default();
+                let c = u32::from_str_radix(code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error(format!("invalid unicode escape `\\{c}{code}`")))?;
+                self.pos += len;
+                c
+            }
+            Some(c) => return Err(self.error(format!("invalid escape sequence `\\{c}`"))),
+            None => return Err(self.error("unterminated string")),
+        };
+        Ok(c)
+    }
+
+    /// Parse a heredoc template (e.g. `<<EOT` or `<<-EOT`).
+    fn heredoc(&mut self) -> Result<Expression> {
+        let start = self.pos;
+        self.pos += 2;
+        let indented = self.peek() == Some('-');
+        if indented {
+            self.bump();
+        }
+        let delimiter = self.identifier()?;
+        self.skip_space(false);
+        self.expect('\n')?;
+        let mut lines = Vec::new();
+        loop {
+            if self.peek().is_none() {
+                return Err(self.error(format!("missing heredoc delimiter `{delimiter}`")));
+            }
+            let rest = self.rest();
+            let end = rest.find('\n').unwrap_or(rest.len());
+            let line = &rest[..end];
+            self.pos += end;
+            self.bump();
+            if line.trim() == delimiter {
+                break;
+            }
+            lines.push(line);
+        }
+        if indented {
+            let indent = lines
+                .iter()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.len() - line.trim_start().len())
+                .min()
+                .unwrap_or(0);
+            for line in &mut lines {
+                *line = line.get(indent..).unwrap_or_default();
+            }
+        }
+        let content: String = lines.iter().map(|line| format!("{line}\n")).collect();
+        let template = parse_template(&content).map_err(|err| {
+            self.pos = start;
+            self.error(format!("invalid heredoc: {err}"))
+        })?;
+        Ok(Expression::Template(template))
+    }
+
+    /// Skip the content of a template sequence (i.e. `${...}` or `%{...}`) including the closing
+    /// brace.
+    fn skip_nested(&mut self) -> Result<()> {
+        let mut depth = 1;
+        loop {
+            match self.bump() {
+                None => return Err(self.error("unterminated template sequence")),
+                Some('"') => self.skip_string()?,
+                Some('{') => depth += 1,
+                Some('}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Skip a quoted template. The opening quote must already be consumed.
+    fn skip_string(&mut self) -> Result<()> {
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("$${") || rest.starts_with("%%{") {
+                self.pos += 3;
+            } else if rest.starts_with("${") || rest.starts_with("%{") {
+                self.pos += 2;
+                self.skip_nested()?;
+            } else {
+                if matches!(self.peek(), None | Some('\n')) {
+                    return Err(self.error("unterminated string"));
+                }
+                match self.bump() {
+                    Some('"') => return Ok(()),
+                    Some('\\') => {
+                        self.bump();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Consume `keyword` if it is the next word.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let Some(rest) = self.rest().strip_prefix(keyword) else {
+            return false;
+        };
+        if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '-') {
+            return false;
+        }
+        self.pos += keyword.len();
+        true
+    }
+
+    /// Skip an expression after checking its syntax. New lines are skipped only if `newlines`
+    /// is set (e.g. inside of brackets).
+    fn skip_expression(&mut self, newlines: bool) -> Result<()> {
+        self.skip_operand(newlines)?;
+        loop {
+            self.skip_space(newlines);
+            let rest = self.rest();
+            let operator = ["==", "!=", "<=", ">=", "&&", "||"]
+                .into_iter()
+                .find(|operator| rest.starts_with(operator))
+                .map(str::len)
+                .or_else(|| {
+                    rest.starts_with(['<', '>', '+', '-', '*', '/', '%'])
+                        .then_some(1)
+                });
+            if let Some(len) = operator {
+                self.pos += len;
+                self.skip_operand(newlines)?;
+            } else if self.peek() == Some('?') {
+                self.bump();
+                self.skip_expression(newlines)?;
+                self.skip_space(newlines);
+                self.expect(':')?;
+                self.skip_expression(newlines)?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skip an operand of an operation including unary operators, indices, attributes and
+    /// splats (e.g. `-var.names[*].length`).
+    fn skip_operand(&mut self, newlines: bool) -> Result<()> {
+        self.skip_space(newlines);
+        while matches!(self.peek(), Some('-' | '!')) {
+            self.bump();
+            self.skip_space(newlines);
+        }
+        match self.peek() {
+            Some('"') => {
+                self.bump();
+                self.template(true)?;
+            }
+            Some('<') if self.rest().starts_with("<<") => {
+                self.heredoc()?;
+            }
+            Some('[') => {
+                self.bump();
+                self.skip_space(true);
+                if self.keyword("for") {
+                    self.skip_for(false)?;
+                } else {
+                    self.skip_items(']')?;
+                }
+            }
+            Some('{') => {
+                self.bump();
+                self.skip_space(true);
+                if self.keyword("for") {
+                    self.skip_for(true)?;
+                } else {
+                    self.skip_fields()?;
+                }
+            }
+            Some('(') => {
+                self.bump();
+                self.skip_expression(true)?;
+                self.skip_space(true);
+                self.expect(')')?;
+            }
+            Some(c) if c.is_ascii_digit() => {
+                self.number()?;
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                self.identifier()?;
+                // Provider-defined functions (e.g. `provider::kubernetes::manifest_decode`).
+                while self.rest().starts_with("::") {
+                    self.pos += 2;
+                    self.identifier()?;
+                }
+                if self.peek() == Some('(') {
+                    self.bump();
+                    self.skip_items(')')?;
+                }
+            }
+            Some(c) => return Err(self.error(format!("expected expression, found `{c}`"))),
+            None => return Err(self.error("expected expression, found end of file")),
+        }
+        loop {
+            match self.peek() {
+                Some('.') if self.rest().starts_with("...") => return Ok(()),
+                Some('.') => {
+                    self.bump();
+                    match self.peek() {
+                        Some('*') => {
+                            self.bump();
+                        }
+                        Some(c) if c.is_ascii_digit() => {
+                            self.number()?;
+                        }
+                        _ => {
+                            self.identifier()?;
+                        }
+                    }
+                }
+                Some('[') => {
+                    self.bump();
+                    self.skip_space(true);
+                    if self.peek() == Some('*') {
+                        self.bump();
+                    } else {
+                        self.skip_expression(true)?;
+                    }
+                    self.skip_space(true);
+                    self.expect(']')?;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Skip the comma-separated elements of a tuple or the arguments of a function call
+    /// including the closing bracket `close`. The opening bracket must already be consumed.
+    fn skip_items(&mut self, close: char) -> Result<()> {
+        loop {
+            self.skip_space(true);
+            if self.peek() == Some(close) {
+                self.bump();
+                return Ok(());
+            }
+            self.skip_expression(true)?;
+            self.skip_space(true);
+            if close == ')' && self.rest().starts_with("...") {
+                self.pos += 3;
+                self.skip_space(true);
+                return self.expect(close);
+            }
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(c) if c == close => {}
+                Some(c) => {
+                    return Err(self.error(format!("expected `,` or `{close}`, found `{c}`")))
+                }
+                None => return Err(self.error(format!("expected `{close}`, found end of file"))),
+            }
+        }
+    }
+
+    /// Skip the fields of an object including the closing brace. The opening brace must
+    /// already be consumed.
+    fn skip_fields(&mut self) -> Result<()> {
+        loop {
+            self.skip_space(true);
+            if self.peek() == Some('}') {
+                self.bump();
+                return Ok(());
+            }
+            self.skip_expression(false)?;
+            self.skip_space(false);
+            match self.peek() {
+                Some('=' | ':') => {
+                    self.bump();
+                }
+                Some(c) => return Err(self.error(format!("expected `=`, found `{c}`"))),
+                None => return Err(self.error("expected `=`, found end of file")),
+            }
+            self.skip_expression(false)?;
+            self.skip_space(false);
+            match self.peek() {
+                Some(',' | '\n') => {
+                    self.bump();
+                }
+                Some('}') => {}
+                Some(c) => {
+                    return Err(self.error(format!("expected new line, `,` or `}}`, found `{c}`")))
+                }
+                None => return Err(self.error("expected `}`, found end of file")),
+            }
+        }
+    }
+
+    /// Skip a `for` expression following the keyword `for` including the closing bracket. If
+    /// `object` is set, the expression is terminated by `}` and produces an object.
+    fn skip_for(&mut self, object: bool) -> Result<()> {
+        self.skip_space(true);
+        self.identifier()?;
+        self.skip_space(true);
+        if self.peek() == Some(',') {
+            self.bump();
+            self.skip_space(true);
+            self.identifier()?;
+            self.skip_space(true);
+        }
+        if !self.keyword("in") {
+            return Err(self.error("expected `in`"));
+        }
+        self.skip_expression(true)?;
+        self.skip_space(true);
+        self.expect(':')?;
+        self.skip_expression(true)?;
+        self.skip_space(true);
+        if object {
+            if !self.rest().starts_with("=>") {
+                return Err(self.error("expected `=>`"));
+            }
+            self.pos += 2;
+            self.skip_expression(true)?;
+            self.skip_space(true);
+            if self.rest().starts_with("...") {
+                self.pos += 3;
+                self.skip_space(true);
+            }
+        }
+        if self.keyword("if") {
+            self.skip_expression(true)?;
+            self.skip_space(true);
+        }
+        self.expect(if object { '}' } else { ']' })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Expression, Item, Part, Step};
+    use super::parse;
+
+    #[test]
+    fn parse_resource() {
+        let body = parse(
+            r#"
+            # comment
+            resource "kubernetes_pod" "nginx" {
+              metadata {
+                name   = "nginx-${var.name}" // comment
+                labels = { app = "nginx", "app.kubernetes.io/name" = "nginx" }
+              }
+              count = length(var.names) + 1
+              ports = [80, 443]
+              script = <<-EOT
+                echo "${var.name}"
+                EOT
+            }
+            "#,
+        )
+        .unwrap();
+        let [Item::Block(block)] = body.items.as_slice() else {
+            panic!("expected a single block");
+        };
+        assert_eq!(block.labels, ["kubernetes_pod", "nginx"]);
+        let [Item::Block(metadata), ..] = block.body.items.as_slice() else {
+            panic!("expected metadata block");
+        };
+        let name = Expression::Template(vec![
+            Part::Literal("nginx-".to_string()),
+            Part::Interpolation(Expression::Traversal(
+                "var".to_string(),
+                vec![Step::Attribute("name".to_string())],
+            )),
+        ]);
+        assert_eq!(metadata.body.attribute("name"), Some(&name));
+        let labels = metadata.body.attribute("labels").unwrap();
+        assert!(matches!(labels, Expression::Object(fields) if fields.len() == 2));
+        let count = block.body.attribute("count").unwrap();
+        assert_eq!(count, &Expression::Raw("length(var.names) + 1".to_string()));
+        let ports = block.body.attribute("ports").unwrap();
+        assert_eq!(ports.to_string(), "[80, 443]");
+        let script = block.body.attribute("script").unwrap();
+        assert_eq!(script.to_string(), "\"echo \\\"${var.name}\\\"\\n\"");
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let error = parse("resource \"a\" \"b\" {\n  name = \"a\n}").unwrap_err();
+        assert_eq!(error.to_string(), "2:12: unterminated string");
+        assert!(parse("resource \"a\" {").is_err());
+        let error = |source: &str| parse(source).unwrap_err().to_string();
+        assert_eq!(
+            error("b = 1 +"),
+            "1:8: expected expression, found end of file"
+        );
+        assert_eq!(
+            error("a = \"ü\" b = 2"),
+            "1:10: expected new line, found `b`"
+        );
+        assert_eq!(error("a = 1.5e"), "1:9: expected digits of exponent");
+        assert_eq!(error("a = f(1,, 2)"), "1:9: expected expression, found `,`");
+        assert_eq!(
+            error("a = \"${var.a +}\""),
+            "1:15: expected expression, found `}`"
+        );
+        assert_eq!(error("a = [for x in y x]"), "1:17: expected `:`, found `x`");
+    }
+
+    #[test]
+    fn parse_raw() {
+        let body = parse(
+            r#"
+            a = [for name, value in var.map : upper(name) if value != null]
+            b = { for pod in var.pods : pod.name => pod... }
+            c = var.pods[*].metadata[0].name[var.index + 1]
+            d = var.enabled ? provider::kubernetes::manifest_decode(var.yaml) : {}
+            e = !(var.count >= 2 && var.count % 2 == 0)
+            f = "${join(", ", var.names)}"
+            "#,
+        )
+        .unwrap();
+        let raw = |name: &str| match body.attribute(name) {
+            Some(Expression::Raw(raw)) => raw.clone(),
+            _ => panic!("expected raw expression `{name}`"),
+        };
+        let c = body.attribute("c").unwrap();
+        assert_eq!(c.to_string(), "var.pods[*].metadata[0].name[var.index + 1]");
+        assert_eq!(raw("e"), "!(var.count >= 2 && var.count % 2 == 0)");
+        for name in ["a", "b", "d"] {
+            raw(name);
+        }
+        let f = body.attribute("f").unwrap();
+        assert_eq!(f.to_string(), "\"${join(\", \", var.names)}\"");
+    }
+}
@@ -0,0 +1,251 @@
+//! Reader of Terraform's JSON syntax. Whether a JSON object is a nested block or an attribute
+//! is decided using the provider schema.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{Map, Value};
+use tf_bindgen_schema::provider::v1_0::{self, Type};
+
+use super::hcl::{parse_expression, parse_template};
+use super::{Block, Body, Expression, Importer, Item, Part};
+
+/// Parse a configuration written in Terraform's JSON syntax.
+pub(super) fn parse(source: &str, importer: &Importer) -> Result<Body> {
+    let document: Value = serde_json::from_str(source).context("invalid JSON document")?;
+    let mut body = Body::default();
+    for (key, value) in object(&document)? {
+        match key.as_str() {
+            "//" => {}
+            "resource" | "data" => {
+                for (ty, objects) in object(value)? {
+                    let schema = importer
+                        .find(key == "data", ty)
+                        .map(|(_, schema)| &schema.block);
+                    for (name, config) in object(objects)? {
+                        let config = config_body(config, schema)
+                            .with_context(|| format!("{key} `{ty}.{name}`"))?;
+                        body.items.push(block(key, [ty, name], config));
+                    }
+                }
+            }
+            "provider" => {
+                for (name, configs) in object(value)? {
+                    let schema = importer
+                        .find_provider(name)
+                        .map(|(_, schema)| &schema.block);
+                    for config in one_or_many(configs) {
+                        let config = config_body(config, schema)
+                            .with_context(|| format!("provider `{name}`"))?;
+                        body.items.push(block("provider", [name], config));
+                    }
+                }
+            }
+            "locals" => {
+                let items = object(value)?
+                    .iter()
+                    .map(|(name, value)| Ok(Item::Attribute(name.clone(), expression(value)?)))
+                    .collect::<Result<_>>()?;
+                body.items.push(block("locals", [], Body { items }));
+            }
+            "terraform" => {
+                let mut terraform = Body::default();
+                let backends = object(value)?.get("backend").map(object).transpose()?;
+                for (kind, config) in backends.into_iter().flatten() {
+                    let config = attributes(config, literal)?;
+                    terraform.items.push(block("backend", [kind], config));
+                }
+                body.items.push(block("terraform", [], terraform));
+            }
+            _ => {
+                for (name, config) in object(value)? {
+                    let config = match key.as_str() {
+                        "variable" => variable_body(config),
+                        "output" => output_body(config),
+                        _ => config_body(config, None),
+                    }
+                    .with_context(|| format!("{key} `{name}`"))?;
+                    body.items.push(block(key, [name], config));
+                }
+            }
+        }
+    }
+    Ok(body)
+}
+
+fn block<const N: usize>(ty: &str, labels: [&String; N], body: Body) -> Item {
+    Item::Block(Block {
+        ty: ty.to_string(),
+        labels: labels.into_iter().cloned().collect(),
+        body,
+    })
+}
+
+fn object(value: &Value) -> Result<&Map<String, Value>> {
+    value
+        .as_object()
+        .ok_or_else(|| anyhow!("expected JSON object, found `{value}`"))
+}
+
+/// Returns the elements of `value` if it is an array or `value` itself otherwise.
+fn one_or_many(value: &Value) -> impl Iterator<Item = &Value> {
+    match value {
+        Value::Array(items) => items.iter().collect::<Vec<_>>(),
+        value => vec![value],
+    }
+    .into_iter()
+}
+
+/// Convert the configuration of a provider, resource, data source or output. Nested blocks are
+/// detected using `schema`. Without schema, every list of objects is read as nested blocks.
+fn config_body(config: &Value, schema: Option<&v1_0::Block>) -> Result<Body> {
+    let mut body = Body::default();
+    for (key, value) in object(config)? {
+        let nested = schema
+            .and_then(|schema| schema.block_types.as_ref()?.get(key))
+            .map(|ty| match ty {
+                Type::Single { block } | Type::List { block, .. } => &**block,
+            });
+        let is_blocks = match value {
+            Value::Array(items) => !items.is_empty() && items.iter().all(Value::is_object),
+            _ => false,
+        };
+        match key.as_str() {
+            "//" => {}
+            "depends_on" => body
+                .items
+                .push(Item::Attribute(key.clone(), references(value)?)),
+            "provider" => {
+                let provider = value.as_str().ok_or_else(|| anyhow!("invalid provider"))?;
+                let provider = parse_expression(provider)?;
+                body.items.push(Item::Attribute(key.clone(), provider));
+            }
+            "lifecycle" => {
+                let lifecycle = lifecycle_body(value)?;
+                body.items.push(block(key, [], lifecycle));
+            }
+            _ if nested.is_some() || (schema.is_none() && is_blocks) => {
+                for config in one_or_many(value).filter(|value| !value.is_null()) {
+                    body.items
+                        .push(block(key, [], config_body(config, nested)?));
+                }
+            }
+            _ => body
+                .items
+                .push(Item::Attribute(key.clone(), expression(value)?)),
+        }
+    }
+    Ok(body)
+}
+
+fn lifecycle_body(config: &Value) -> Result<Body> {
+    let mut body = Body::default();
+    for (key, value) in object(config)? {
+        let value = match (key.as_str(), value) {
+            ("ignore_changes", Value::String(all)) => parse_expression(all)?,
+            ("ignore_changes" | "replace_triggered_by", value) => references(value)?,
+            ("precondition" | "postcondition", conditions) => {
+                for condition in one_or_many(conditions) {
+                    let condition = attributes(condition, expression)?;
+                    body.items.push(block(key, [], condition));
+                }
+                continue;
+            }
+            (_, value) => literal(value)?,
+        };
+        body.items.push(Item::Attribute(key.clone(), value));
+    }
+    Ok(body)
+}
+
+fn variable_body(config: &Value) -> Result<Body> {
+    let mut body = Body::default();
+    for (key, value) in object(config)? {
+        let value = match (key.as_str(), value) {
+            ("type", Value::String(ty)) => parse_expression(ty)?,
+            ("validation", validations) => {
+                for validation in one_or_many(validations) {
+                    let validation = attributes(validation, expression)?;
+                    body.items.push(block(key, [], validation));
+                }
+                continue;
+            }
+            (_, value) => literal(value)?,
+        };
+        body.items.push(Item::Attribute(key.clone(), value));
+    }
+    Ok(body)
+}
+
+fn output_body(config: &Value) -> Result<Body> {
+    let mut body = Body::default();
+    for (key, value) in object(config)? {
+        let value = match key.as_str() {
+            "value" => expression(value)?,
+            "depends_on" => references(value)?,
+            _ => literal(value)?,
+        };
+        body.items.push(Item::Attribute(key.clone(), value));
+    }
+    Ok(body)
+}
+
+/// Convert every field of `config` into an attribute using `convert`.
+fn attributes(config: &Value, convert: fn(&Value) -> Result<Expression>) -> Result<Body> {
+    let items = object(config)?
+        .iter()
+        .map(|(key, value)| Ok(Item::Attribute(key.clone(), convert(value)?)))
+        .collect::<Result<_>>()?;
+    Ok(Body { items })
+}
+
+/// Convert a list of references (e.g. `depends_on`).
+fn references(value: &Value) -> Result<Expression> {
+    let references = one_or_many(value)
+        .map(|reference| {
+            let reference = reference
+                .as_str()
+                .ok_or_else(|| anyhow!("expected reference, found `{reference}`"))?;
+            let reference = reference
+                .strip_prefix("${")
+                .and_then(|reference| reference.strip_suffix('}'))
+                .unwrap_or(reference);
+            parse_expression(reference)
+        })
+        .collect::<Result<_>>()?;
+    Ok(Expression::Tuple(references))
+}
+
+/// Convert `value` into an expression. Strings are read as templates.
+fn expression(value: &Value) -> Result<Expression> {
+    convert(value, &|text| {
+        Ok(Expression::Template(parse_template(text)?))
+    })
+}
+
+/// Convert `value` into an expression. Strings are read as is.
+fn literal(value: &Value) -> Result<Expression> {
+    convert(value, &|text| {
+        Ok(Expression::Template(vec![Part::Literal(text.to_string())]))
+    })
+}
+
+fn convert(value: &Value, string: &dyn Fn(&str) -> Result<Expression>) -> Result<Expression> {
+    let expression = match value {
+        Value::Null => Expression::Null,
+        Value::Bool(value) => Expression::Bool(*value),
+        Value::Number(value) => Expression::Number(value.to_string()),
+        Value::String(text) => string(text)?,
+        Value::Array(items) => Expression::Tuple(
+            items
+                .iter()
+                .map(|item| convert(item, string))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(fields) => Expression::Object(
+            fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), convert(value, string)?)))
+                .collect::<Result<_>>()?,
+        ),
+    };
+    Ok(expression)
+}
@@ -0,0 +1,393 @@
+//! Used to convert existing Terraform configurations (`.tf` or `.tf.json` files) into Rust code
+//! using the generated bindings. See [`Importer`].
+
+mod hcl;
+mod json;
+mod rust;
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use tf_bindgen_schema::provider::{self, v1_0};
+
+use crate::config::ProviderConfig;
+
+/// Used to convert Terraform configurations into Rust code. Resources and data sources are
+/// written using `tf_bindgen::codegen::resource!`, providers using their builders. References
+/// to other objects of the configuration are translated into field accesses (e.g.
+/// `&nginx.metadata[0].name`). Expressions without a Rust equivalent (e.g. function calls)
+/// are passed to Terraform as is (see [`crate::Value::raw_expression`]). Parts which cannot
+/// be converted are marked with `TODO` comments.
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let bindings = tf_bindgen::Builder::default().config("terraform.toml").generate()?;
+/// let code = bindings.importer().bindings_module("crate::terraform").import_path("main.tf")?;
+/// ```
+pub struct Importer<'a> {
+    schema: &'a provider::Schema,
+    providers: &'a [ProviderConfig],
+    bindings_module: String,
+    function_name: String,
+}
+
+/// Body of a block, i.e. its attributes and nested blocks in the order of declaration.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Body {
+    items: Vec<Item>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Item {
+    Attribute(String, Expression),
+    Block(Block),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Block {
+    ty: String,
+    labels: Vec<String>,
+    body: Body,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expression {
+    Null,
+    Bool(bool),
+    Number(String),
+    Template(Vec<Part>),
+    /// A reference to an object or value (e.g. `kubernetes_pod.nginx.metadata[0].name`).
+    Traversal(String, Vec<Step>),
+    Tuple(Vec<Expression>),
+    Object(Vec<(String, Expression)>),
+    /// Any other expression (e.g. function calls) stored as written.
+    Raw(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Literal(String),
+    Interpolation(Expression),
+    /// A template directive (e.g. `%{ if ... }`) stored as written.
+    Directive(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    Attribute(String),
+    Index(Expression),
+    Splat,
+}
+
+impl<'a> Importer<'a> {
+    /// Create an importer using the provider `schema` and `providers` used to generate the
+    /// bindings. See [`crate::Bindings::importer`].
+    pub fn new(schema: &'a provider::Schema, providers: &'a [ProviderConfig]) -> Self {
+        Self {
+            schema,
+            providers,
+            bindings_module: "crate::terraform".to_string(),
+            function_name: "build".to_string(),
+        }
+    }
+
+    /// Set the path of the module containing the generated bindings (default:
+    /// `crate::terraform`).
+    pub fn bindings_module(&mut self, path: impl Into<String>) -> &mut Self {
+        self.bindings_module = path.into();
+        self
+    }
+
+    /// Set the name of the generated function (default: `build`).
+    pub fn function_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.function_name = name.into();
+        self
+    }
+
+    /// Convert the configuration at `path` into Rust code. `path` can either be a single file
+    /// or a directory, in which case all `.tf` and `.tf.json` files of this directory are
+    /// converted into one function.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a file cannot be read or parsed.
+    pub fn import_path(&self, path: impl AsRef<Path>) -> Result<String> {
+        let path = path.as_ref();
+        let files = if path.is_dir() {
+            std::fs::read_dir(path)
+                .with_context(|| format!("failed to read directory {}", path.display()))?
+                .map(|entry| Ok(entry?.path()))
+                .filter_ok(|path| {
+                    let name = path.to_string_lossy();
+                    name.ends_with(".tf") || name.ends_with(".tf.json")
+                })
+                .collect::<std::io::Result<Vec<_>>>()?
+                .into_iter()
+                .sorted()
+                .collect()
+        } else {
+            vec![path.to_path_buf()]
+        };
+        let mut body = Body::default();
+        for file in files {
+            let source = std::fs::read_to_string(&file)
+                .with_context(|| format!("failed to read {}", file.display()))?;
+            let file_body = if file.to_string_lossy().ends_with(".json") {
+                json::parse(&source, self)
+            } else {
+                hcl::parse(&source)
+            }
+            .with_context(|| format!("failed to parse {}", file.display()))?;
+            body.items.extend(file_body.items);
+        }
+        Ok(rust::generate(self, &body))
+    }
+
+    /// Convert a configuration written in Terraform's native syntax into Rust code.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `source` is not a valid configuration.
+    pub fn import_hcl(&self, source: &str) -> Result<String> {
+        Ok(rust::generate(self, &hcl::parse(source)?))
+    }
+
+    /// Convert a configuration written in Terraform's JSON syntax into Rust code.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `source` is not a valid configuration.
+    pub fn import_json(&self, source: &str) -> Result<String> {
+        Ok(rust::generate(self, &json::parse(source, self)?))
+    }
+
+    /// Returns the configuration and schema of the provider of the resource (or data source)
+    /// `ty`.
+    fn find(&self, data: bool, ty: &str) -> Option<(&ProviderConfig, &v1_0::Schema)> {
+        let provider::Schema::V1_0 { provider_schemas } = self.schema else {
+            return None;
+        };
+        provider_schemas.iter().find_map(|(url, schema)| {
+            let schemas = if data {
+                &schema.data_source_schemas
+            } else {
+                &schema.resource_schemas
+            };
+            let config = self.providers.iter().find(|config| config.matches(url))?;
+            Some((config, schemas.get(ty)?))
+        })
+    }
+
+    /// Returns the configuration and schema of the provider with the local name `name`.
+    fn find_provider(&self, name: &str) -> Option<(&ProviderConfig, &v1_0::Schema)> {
+        let provider::Schema::V1_0 { provider_schemas } = self.schema else {
+            return None;
+        };
+        provider_schemas.iter().find_map(|(url, schema)| {
+            let config = self.providers.iter().find(|config| config.matches(url))?;
+            (url.split('/').next_back() == Some(name)).then_some((config, &schema.provider))
+        })
+    }
+}
+
+impl Body {
+    fn attribute(&self, name: &str) -> Option<&Expression> {
+        self.items.iter().find_map(|item| match item {
+            Item::Attribute(key, value) if key == name => Some(value),
+            _ => None,
+        })
+    }
+}
+
+impl Expression {
+    /// Returns the literal content of this expression if it is a template without
+    /// interpolations.
+    fn as_literal(&self) -> Option<String> {
+        match self {
+            Expression::Template(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    Part::Literal(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Expression {
+    /// Write this expression using Terraform's native syntax.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expression::Null => write!(f, "null"),
+            Expression::Bool(value) => write!(f, "{value}"),
+            Expression::Number(value) => write!(f, "{value}"),
+            Expression::Template(parts) => {
+                write!(f, "\"")?;
+                for part in parts {
+                    match part {
+                        Part::Literal(text) => write!(f, "{}", escape(text))?,
+                        Part::Interpolation(expression) => write!(f, "${{{expression}}}")?,
+                        Part::Directive(directive) => write!(f, "{directive}")?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Expression::Traversal(root, steps) => {
+                write!(f, "{root}")?;
+                for step in steps {
+                    match step {
+                        Step::Attribute(name) => write!(f, ".{name}")?,
+                        Step::Index(index) => write!(f, "[{index}]")?,
+                        Step::Splat => write!(f, "[*]")?,
+                    }
+                }
+                Ok(())
+            }
+            Expression::Tuple(items) => write!(f, "[{}]", items.iter().join(", ")),
+            Expression::Object(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(key, value)| format!("\"{}\" = {value}", escape(key)));
+                write!(f, "{{ {} }}", fields.format(", "))
+            }
+            Expression::Raw(expression) => write!(f, "{expression}"),
+        }
+    }
+}
+
+/// Escape `text` to be used inside of a quoted template.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+        .replace("${", "$${")
+        .replace("%{", "%%{")
+}
+
+#[cfg(test)]
+mod tests {
+    use tf_bindgen_schema::provider::Schema;
+
+    use super::Importer;
+    use crate::config::ProviderConfig;
+    use crate::version::VersionConstraint;
+
+    /// Provider schema also used to compile imported code (see `crates/import-test`).
+    const SCHEMA: &str = include_str!("../../crates/import-test/fixtures/schema.json");
+
+    /// Returns the schema and configuration of the kubernetes provider used by the tests.
+    pub(super) fn kubernetes() -> (Schema, Vec<ProviderConfig>) {
+        let schema = serde_json::from_str(SCHEMA).unwrap();
+        let version = VersionConstraint::parse("=2.19.0").unwrap();
+        let providers = vec![ProviderConfig::new("hashicorp/kubernetes", version)];
+        (schema, providers)
+    }
+
+    #[test]
+    fn import_json() {
+        let (schema, providers) = kubernetes();
+        let code = Importer::new(&schema, &providers)
+            .import_json(
+                r#"{
+                    "terraform": {
+                        "backend": { "local": { "path": "state/terraform.tfstate" } }
+                    },
+                    "provider": {
+                        "kubernetes": [
+                            { "config_path": "~/.kube/config" },
+                            { "alias": "west", "host": "https://west.example.com" }
+                        ]
+                    },
+                    "data": {
+                        "kubernetes_namespace": {
+                            "default": { "metadata": [{ "name": "default" }] }
+                        }
+                    },
+                    "resource": {
+                        "kubernetes_pod": {
+                            "nginx": {
+                                "provider": "kubernetes.west",
+                                "metadata": [{
+                                    "name": "nginx",
+                                    "namespace": "${data.kubernetes_namespace.default.metadata[0].name}"
+                                }],
+                                "lifecycle": {
+                                    "ignore_changes": "all",
+                                    "precondition": [{
+                                        "condition": "${self.replicas > 0}",
+                                        "error_message": "no replicas"
+                                    }]
+                                }
+                            }
+                        }
+                    },
+                    "output": {
+                        "name": {
+                            "value": "${kubernetes_pod.nginx.metadata[0].name}",
+                            "description": "Name of the pod"
+                        }
+                    }
+                }"#,
+            )
+            .unwrap();
+        let expected = r#"use std::rc::Rc;
+
+use crate::terraform::kubernetes::Kubernetes;
+use crate::terraform::kubernetes::data::kubernetes_namespace;
+use crate::terraform::kubernetes::resource::kubernetes_pod;
+use tf_bindgen::Stack;
+
+pub fn build(stack: &Rc<Stack>) {
+    stack.backend(tf_bindgen::backend::Backend::Custom {
+        kind: "local".to_string(),
+        config: tf_bindgen::json::json!({ "path": "state/terraform.tfstate" }).as_object().cloned().unwrap(),
+    });
+    Kubernetes::create(stack)
+        .config_path("~/.kube/config")
+        .build();
+    let west = Kubernetes::create(stack)
+        .alias("west")
+        .host("https://west.example.com")
+        .build();
+    let default = tf_bindgen::codegen::resource! {
+        stack,
+        data "kubernetes_namespace" "default" {
+            metadata {
+                name = "default"
+            }
+        }
+    };
+    let nginx = tf_bindgen::codegen::resource! {
+        stack,
+        resource "kubernetes_pod" "nginx" {
+            lifecycle {
+                ignore_changes = all
+                precondition {
+                    condition = "self.replicas > 0"
+                    error_message = "no replicas"
+                }
+            }
+            metadata {
+                name = "nginx"
+                namespace = &default.metadata[0].name
+            }
+            provider = &*west
+        }
+    };
+    tf_bindgen::output!(
+        stack,
+        "name" = &nginx.metadata[0].name,
+        description = "Name of the pod",
+    );
+}
+"#;
+        assert_eq!(code, expected);
+    }
+}
@@ -0,0 +1,1259 @@
+//! Writer of the Rust code of an imported configuration.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use itertools::Itertools;
+use tf_bindgen_schema::provider::v1_0::{self, BlockType, Type};
+
+use super::{Block, Body, Expression, Importer, Item, Part, Step};
+use crate::codegen::field_info::fix_ident;
+
+/// A top-level object of a configuration.
+struct Declaration<'b> {
+    /// Key used to reference this object (e.g. `var.name` or `kubernetes_pod.nginx`).
+    key: String,
+    kind: Kind<'b>,
+}
+
+enum Kind<'b> {
+    Backend(&'b Block),
+    Provider(&'b str, &'b Body),
+    Variable(&'b str, &'b Body),
+    Local(&'b str, &'b Expression),
+    Resource(&'b str, &'b str, &'b Body),
+    DataSource(&'b str, &'b str, &'b Body),
+    Output(&'b str, &'b Body),
+    Unsupported(String),
+}
+
+/// A declaration, which was assigned to a local variable.
+enum Binding<'a> {
+    Value(String, BlockType),
+    Object(String, &'a v1_0::Block),
+    Provider(String),
+}
+
+struct Generator<'a> {
+    importer: &'a Importer<'a>,
+    /// Names of the local variables of all referenced declarations.
+    idents: HashMap<String, String>,
+    bindings: HashMap<String, Binding<'a>>,
+    imports: BTreeSet<String>,
+    /// Resource and data source modules imported by name.
+    modules: HashMap<String, String>,
+    lines: Vec<String>,
+}
+
+/// Generate the Rust function declaring the objects of `body`.
+pub(super) fn generate(importer: &Importer, body: &Body) -> String {
+    let declarations = declarations(body);
+    let dependencies: Vec<_> = declarations.iter().map(Declaration::dependencies).collect();
+    let referenced = dependencies.iter().flatten().cloned().collect();
+    let mut generator = Generator {
+        importer,
+        idents: idents(&declarations, &referenced),
+        bindings: HashMap::new(),
+        imports: BTreeSet::new(),
+        modules: HashMap::new(),
+        lines: Vec::new(),
+    };
+    for index in order(&declarations, &dependencies) {
+        generator.declaration(&declarations[index]);
+    }
+    generator.finish()
+}
+
+fn declarations(body: &Body) -> Vec<Declaration<'_>> {
+    let mut declarations = Vec::new();
+    let mut push = |key: String, kind| declarations.push(Declaration { key, kind });
+    for item in &body.items {
+        let block = match item {
+            Item::Attribute(name, _) => {
+                push(
+                    String::new(),
+                    Kind::Unsupported(format!("attribute `{name}`")),
+                );
+                continue;
+            }
+            Item::Block(block) => block,
+        };
+        match (block.ty.as_str(), block.labels.as_slice()) {
+            ("provider", [name]) => {
+                let key = match block
+                    .body
+                    .attribute("alias")
+                    .and_then(Expression::as_literal)
+                {
+                    Some(alias) => format!("provider.{name}.{alias}"),
+                    None => format!("provider.{name}"),
+                };
+                push(key, Kind::Provider(name, &block.body))
+            }
+            ("variable", [name]) => push(format!("var.{name}"), Kind::Variable(name, &block.body)),
+            ("locals", []) => {
+                for item in &block.body.items {
+                    if let Item::Attribute(name, value) = item {
+                        push(format!("local.{name}"), Kind::Local(name, value));
+                    }
+                }
+            }
+            ("resource", [ty, name]) => push(
+                format!("{ty}.{name}"),
+                Kind::Resource(ty, name, &block.body),
+            ),
+            ("data", [ty, name]) => push(
+                format!("data.{ty}.{name}"),
+                Kind::DataSource(ty, name, &block.body),
+            ),
+            ("output", [name]) => push(format!("output.{name}"), Kind::Output(name, &block.body)),
+            ("terraform", []) => {
+                for item in &block.body.items {
+                    match item {
+                        Item::Block(backend) if backend.ty == "backend" => {
+                            push(String::new(), Kind::Backend(backend))
+                        }
+                        // providers are configured using the bindings
+                        Item::Block(block) if block.ty == "required_providers" => {}
+                        Item::Attribute(name, _) if name == "required_version" => {}
+                        Item::Block(block) => push(
+                            String::new(),
+                            Kind::Unsupported(format!("block `{}`", block_name(block))),
+                        ),
+                        Item::Attribute(name, _) => push(
+                            String::new(),
+                            Kind::Unsupported(format!("terraform setting `{name}`")),
+                        ),
+                    }
+                }
+            }
+            _ => push(
+                String::new(),
+                Kind::Unsupported(format!("block `{}`", block_name(block))),
+            ),
+        }
+    }
+    declarations
+}
+
+impl Declaration<'_> {
+    /// Used to order declarations of the same depth.
+    fn priority(&self) -> usize {
+        match self.kind {
+            Kind::Backend(_) => 0,
+            Kind::Variable(..) => 1,
+            Kind::Local(..) => 2,
+            Kind::Provider(..) => 3,
+            Kind::DataSource(..) => 4,
+            Kind::Resource(..) => 5,
+            Kind::Output(..) => 6,
+            Kind::Unsupported(_) => 7,
+        }
+    }
+
+    /// Returns the keys of all objects referenced by this declaration.
+    fn dependencies(&self) -> BTreeSet<String> {
+        let mut keys = BTreeSet::new();
+        match self.kind {
+            Kind::Provider(_, body) | Kind::Variable(_, body) | Kind::Output(_, body) => {
+                body_references(body, &mut keys)
+            }
+            Kind::Resource(_, _, body) | Kind::DataSource(_, _, body) => {
+                body_references(body, &mut keys);
+                if let Some(Expression::Traversal(name, steps)) = body.attribute("provider") {
+                    keys.extend(provider_key(name, steps));
+                }
+            }
+            Kind::Local(_, value) => references(value, &mut keys),
+            Kind::Backend(_) | Kind::Unsupported(_) => {}
+        }
+        keys
+    }
+}
+
+fn body_references(body: &Body, keys: &mut BTreeSet<String>) {
+    for item in &body.items {
+        match item {
+            Item::Attribute(_, value) => references(value, keys),
+            Item::Block(block) => body_references(&block.body, keys),
+        }
+    }
+}
+
+fn references(expression: &Expression, keys: &mut BTreeSet<String>) {
+    match expression {
+        Expression::Traversal(root, steps) => {
+            keys.extend(key(root, steps).map(|(key, _)| key));
+            for step in steps {
+                if let Step::Index(index) = step {
+                    references(index, keys);
+                }
+            }
+        }
+        Expression::Template(parts) => {
+            for part in parts {
+                if let Part::Interpolation(expression) = part {
+                    references(expression, keys);
+                }
+            }
+        }
+        Expression::Tuple(items) => items.iter().for_each(|item| references(item, keys)),
+        Expression::Object(fields) => fields.iter().for_each(|(_, value)| references(value, keys)),
+        Expression::Null | Expression::Bool(_) | Expression::Number(_) | Expression::Raw(_) => {}
+    }
+}
+
+/// Returns the key of the object referenced by a traversal and the number of steps used to
+/// reference this object.
+fn key(root: &str, steps: &[Step]) -> Option<(String, usize)> {
+    let attribute = |index: usize| match steps.get(index) {
+        Some(Step::Attribute(name)) => Some(name),
+        _ => None,
+    };
+    match root {
+        "var" | "local" => Some((format!("{root}.{}", attribute(0)?), 1)),
+        "data" => Some((format!("data.{}.{}", attribute(0)?, attribute(1)?), 2)),
+        "count" | "each" | "module" | "path" | "self" | "terraform" => None,
+        _ => Some((format!("{root}.{}", attribute(0)?), 1)),
+    }
+}
+
+/// Returns the key of the provider referenced using the `provider` meta-argument.
+fn provider_key(name: &str, steps: &[Step]) -> Option<String> {
+    match steps {
+        [] => Some(format!("provider.{name}")),
+        [Step::Attribute(alias)] => Some(format!("provider.{name}.{alias}")),
+        _ => None,
+    }
+}
+
+/// Assign unique names of local variables to all referenced declarations.
+fn idents(declarations: &[Declaration], referenced: &HashSet<String>) -> HashMap<String, String> {
+    let mut used = HashSet::from(["stack".to_string()]);
+    let mut idents = HashMap::new();
+    for declaration in declarations {
+        if !referenced.contains(&declaration.key) {
+            continue;
+        }
+        let (name, qualified) = match declaration.kind {
+            Kind::Provider(name, body) => {
+                match body.attribute("alias").and_then(Expression::as_literal) {
+                    Some(alias) => (alias.to_snake_case(), format!("{name}_{alias}")),
+                    None => (name.to_snake_case(), format!("{name}_provider")),
+                }
+            }
+            Kind::Variable(name, _) => (name.to_snake_case(), format!("var_{name}")),
+            Kind::Local(name, _) => (name.to_snake_case(), format!("local_{name}")),
+            Kind::Resource(ty, name, _) => (name.to_snake_case(), format!("{ty}_{name}")),
+            Kind::DataSource(ty, name, _) => (name.to_snake_case(), format!("data_{ty}_{name}")),
+            _ => continue,
+        };
+        let qualified = qualified.to_snake_case();
+        let ident = [name, qualified.clone()]
+            .into_iter()
+            .chain((2..).map(|i| format!("{qualified}_{i}")))
+            .find(|ident| !ident.is_empty() && fix_ident(ident) == ident && !used.contains(ident))
+            .unwrap();
+        used.insert(ident.clone());
+        idents.insert(declaration.key.clone(), ident);
+    }
+    idents
+}
+
+/// Order `declarations` such that every declaration is written after the declarations it
+/// references. Declarations without dependencies between each other are ordered by kind and
+/// position.
+fn order(declarations: &[Declaration], dependencies: &[BTreeSet<String>]) -> Vec<usize> {
+    let indices: HashMap<_, _> = declarations
+        .iter()
+        .enumerate()
+        .filter(|(_, declaration)| !declaration.key.is_empty())
+        .map(|(index, declaration)| (declaration.key.as_str(), index))
+        .collect();
+    let mut pending: Vec<HashSet<usize>> = dependencies
+        .iter()
+        .enumerate()
+        .map(|(index, keys)| {
+            keys.iter()
+                .filter_map(|key| indices.get(key.as_str()).copied())
+                .filter(|dependency| *dependency != index)
+                .collect()
+        })
+        .collect();
+    let mut order = Vec::new();
+    let mut done = vec![false; declarations.len()];
+    while order.len() < declarations.len() {
+        let ready = (0..declarations.len())
+            .filter(|index| !done[*index])
+            .filter(|index| pending[*index].is_empty())
+            .min_by_key(|index| (declarations[*index].priority(), *index));
+        // fall back to the order of declaration in case of cyclic references
+        let next = ready.unwrap_or_else(|| done.iter().position(|done| !done).unwrap());
+        done[next] = true;
+        order.push(next);
+        pending.iter_mut().for_each(|pending| {
+            pending.remove(&next);
+        });
+    }
+    order
+}
+
+impl<'a> Generator<'a> {
+    fn declaration(&mut self, declaration: &Declaration) {
+        let key = &declaration.key;
+        match declaration.kind {
+            Kind::Backend(block) => self.backend(block),
+            Kind::Provider(name, body) => self.provider(key, name, body),
+            Kind::Variable(name, body) => self.variable(key, name, body),
+            Kind::Local(name, value) => self.local(key, name, value),
+            Kind::Resource(ty, name, body) => self.object(key, false, ty, name, body),
+            Kind::DataSource(ty, name, body) => self.object(key, true, ty, name, body),
+            Kind::Output(name, body) => self.output(name, body),
+            Kind::Unsupported(ref what) => self.lines.push(format!("// TODO: unsupported {what}")),
+        }
+    }
+
+    /// Write the statement `lines`. The result will be assigned to a local variable if the
+    /// declaration `key` is referenced.
+    fn statement(&mut self, key: &str, mut lines: Vec<String>) -> Option<String> {
+        let ident = self.idents.get(key).cloned();
+        if let Some(ident) = &ident {
+            lines[0] = format!("let {ident} = {}", lines[0]);
+        }
+        if let Some(last) = lines.last_mut() {
+            last.push(';');
+        }
+        self.lines.extend(lines);
+        ident
+    }
+
+    fn backend(&mut self, block: &Block) {
+        let [kind] = block.labels.as_slice() else {
+            self.todo("invalid backend", &block_name(block));
+            return;
+        };
+        let config = block
+            .body
+            .items
+            .iter()
+            .map(|item| match item {
+                Item::Attribute(name, value) => Some(format!("{name:?}: {}", json(value)?)),
+                Item::Block(_) => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(config) = config else {
+            self.todo("unsupported backend configuration", &block_name(block));
+            return;
+        };
+        self.statement(
+            "",
+            vec![
+                "stack.backend(tf_bindgen::backend::Backend::Custom {".to_string(),
+                format!("    kind: {kind:?}.to_string(),"),
+                format!(
+                    "    config: tf_bindgen::json::json!({{ {} }}).as_object().cloned().unwrap(),",
+                    config.join(", ")
+                ),
+                "})".to_string(),
+            ],
+        );
+    }
+
+    fn provider(&mut self, key: &str, name: &str, body: &Body) {
+        let Some((config, schema)) = self.importer.find_provider(name) else {
+            self.lines.push(format!(
+                "// TODO: provider `{name}` is not part of the generated bindings"
+            ));
+            return;
+        };
+        let module = format!(
+            "{}::{}",
+            self.importer.bindings_module,
+            config.module_name()
+        );
+        let ty = name.to_upper_camel_case();
+        self.imports.insert(format!("{module}::{ty}"));
+        let prefix = format!("{module}::");
+        let mut lines = vec![format!("{ty}::create(stack)")];
+        for item in &body.items {
+            let setter = match item {
+                Item::Attribute(name, value) if name == "alias" => {
+                    let alias = self.scalar(value, &BlockType::String);
+                    Ok(format!(".alias({alias})"))
+                }
+                Item::Attribute(name, value) => self
+                    .attribute(&schema.block, name, value, &prefix)
+                    .map(|value| format!(".{}({value})", setter(name)))
+                    .map_err(|reason| todo(reason, &format!("{name} = {value}"))),
+                Item::Block(block) => self
+                    .builder(&schema.block, block, &prefix)
+                    .map(|value| format!(".{}({value})", setter(&block.ty))),
+            };
+            lines.push(format!("    {}", setter.unwrap_or_else(|todo| todo)));
+        }
+        lines.push("    .build()".to_string());
+        if let Some(ident) = self.statement(key, lines) {
+            self.bindings
+                .insert(key.to_string(), Binding::Provider(ident));
+        }
+    }
+
+    fn variable(&mut self, key: &str, name: &str, body: &Body) {
+        let constraint = body.attribute("type");
+        let ty = match constraint.map(ToString::to_string).as_deref() {
+            Some("string") => BlockType::String,
+            Some("bool") => BlockType::Bool,
+            Some("number") => match body.attribute("default") {
+                Some(Expression::Number(number)) if number.parse::<i64>().is_err() => {
+                    BlockType::Dynamic
+                }
+                _ => BlockType::Number,
+            },
+            Some(_) => BlockType::Dynamic,
+            None => match body.attribute("default") {
+                Some(default @ Expression::Template(_)) if default.as_literal().is_some() => {
+                    BlockType::String
+                }
+                Some(Expression::Number(number)) if number.parse::<i64>().is_ok() => {
+                    BlockType::Number
+                }
+                Some(Expression::Bool(_)) => BlockType::Bool,
+                _ => BlockType::Dynamic,
+            },
+        };
+        let mut lines = vec![
+            "stack".to_string(),
+            format!("    .variable::<{}>({name:?})", rust_type(&ty, "")),
+        ];
+        for item in &body.items {
+            let setter = match item {
+                Item::Attribute(name, value) => match (name.as_str(), value) {
+                    ("type", _) => match ty {
+                        BlockType::Dynamic if value.to_string() != "any" => {
+                            Some(format!(".r#type({:?})", value.to_string()))
+                        }
+                        _ => continue,
+                    },
+                    ("default", Expression::Null) => continue,
+                    ("default", value) => {
+                        literal(value, &ty).map(|value| format!(".default({value})"))
+                    }
+                    ("description", value) => value
+                        .as_literal()
+                        .map(|description| format!(".description({description:?})")),
+                    ("sensitive" | "nullable", Expression::Bool(value)) => {
+                        Some(format!(".{name}({value})"))
+                    }
+                    _ => None,
+                }
+                .ok_or_else(|| todo("unsupported setting", &format!("{name} = {value}"))),
+                Item::Block(block) if block.ty == "validation" => condition(&block.body)
+                    .map(|(condition, message)| format!(".validation({condition}, {message})"))
+                    .ok_or_else(|| todo("invalid validation", &block_name(block))),
+                Item::Block(block) => Err(todo("unsupported block", &block_name(block))),
+            };
+            lines.push(format!("    {}", setter.unwrap_or_else(|todo| todo)));
+        }
+        lines.push("    .build()".to_string());
+        if let Some(ident) = self.statement(key, lines) {
+            self.bindings
+                .insert(key.to_string(), Binding::Value(ident, ty));
+        }
+    }
+
+    fn local(&mut self, key: &str, name: &str, value: &Expression) {
+        let ty = match value {
+            Expression::Template(_) => BlockType::String,
+            Expression::Number(number) if number.parse::<i64>().is_ok() => BlockType::Number,
+            Expression::Bool(_) => BlockType::Bool,
+            value => self
+                .resolve(value)
+                .map(|(_, ty)| ty)
+                .unwrap_or(BlockType::Dynamic),
+        };
+        let value = self.scalar(value, &ty);
+        let line = format!("stack.local::<{}>({name:?}, {value})", rust_type(&ty, ""));
+        if let Some(ident) = self.statement(key, vec![line]) {
+            self.bindings
+                .insert(key.to_string(), Binding::Value(ident, ty));
+        }
+    }
+
+    fn object(&mut self, key: &str, data: bool, ty: &str, name: &str, body: &Body) {
+        let kind = if data { "data" } else { "resource" };
+        let Some((config, schema)) = self.importer.find(data, ty) else {
+            self.lines.push(format!(
+                "// TODO: {kind} `{ty}` is not part of the generated bindings"
+            ));
+            return;
+        };
+        if !config.filter.is_match(ty) {
+            self.lines
+                .push(format!("// TODO: {kind} `{ty}` is excluded by the filter"));
+            return;
+        }
+        let module = config.rename.get(ty).map_or(ty, String::as_str);
+        let path = format!(
+            "{}::{}::{kind}::{module}",
+            self.importer.bindings_module,
+            config.module_name()
+        );
+        // a resource and data source of the same name are imported locally
+        let scoped = match self.modules.get(module) {
+            Some(imported) => imported != &path,
+            None => {
+                self.modules.insert(module.to_string(), path.clone());
+                self.imports.insert(path.clone());
+                false
+            }
+        };
+        let type_name = module.to_upper_camel_case();
+        let prefix = match data {
+            true => format!("{module}::Data{type_name}"),
+            false => format!("{module}::{type_name}"),
+        };
+        let mut lines = vec![
+            "tf_bindgen::codegen::resource! {".to_string(),
+            "    stack,".to_string(),
+            format!("    {kind} {module:?} {name:?} {{"),
+        ];
+        self.macro_body(&schema.block, body, &prefix, true, 2, &mut lines);
+        lines.push("    }".to_string());
+        lines.push("}".to_string());
+        if scoped {
+            lines = ["{".to_string(), format!("    use {path};")]
+                .into_iter()
+                .chain(lines.into_iter().map(|line| format!("    {line}")))
+                .chain(["}".to_string()])
+                .collect();
+        }
+        if let Some(ident) = self.statement(key, lines) {
+            self.bindings
+                .insert(key.to_string(), Binding::Object(ident, &schema.block));
+        }
+    }
+
+    /// Write the body of a block used inside of `tf_bindgen::codegen::resource!`. If `meta`
+    /// is set, meta-arguments will be accepted.
+    fn macro_body(
+        &mut self,
+        schema: &v1_0::Block,
+        body: &Body,
+        prefix: &str,
+        meta: bool,
+        indent: usize,
+        lines: &mut Vec<String>,
+    ) {
+        let indentation = "    ".repeat(indent);
+        for item in &body.items {
+            match item {
+                Item::Attribute(name, value) => {
+                    let line = match name.as_str() {
+                        "count" if meta => Ok(self.scalar(value, &BlockType::Number)),
+                        "for_each" if meta => Ok(for_each(value)),
+                        "provider" if meta => {
+                            self.provider_reference(value).ok_or("unknown provider")
+                        }
+                        "depends_on" if meta => self
+                            .object_references(value)
+                            .map(|references| {
+                                let references = references
+                                    .iter()
+                                    .map(|reference| {
+                                        format!("{reference} as &dyn tf_bindgen::L1Construct")
+                                    })
+                                    .join(", ");
+                                format!("&[{references}]")
+                            })
+                            .ok_or("unknown dependency"),
+                        _ => self.attribute(schema, name, value, prefix),
+                    }
+                    .map(|value| format!("{} = {value}", setter(name)))
+                    .unwrap_or_else(|reason| todo(reason, &format!("{name} = {value}")));
+                    lines.push(format!("{indentation}{line}"));
+                }
+                Item::Block(block) if meta && block.ty == "lifecycle" => {
+                    for line in self.lifecycle(&block.body) {
+                        lines.push(format!("{indentation}{line}"));
+                    }
+                }
+                Item::Block(block) => {
+                    let Some(nested) = nested_block(schema, &block.ty) else {
+                        let line = todo("unsupported block", &block_name(block));
+                        lines.push(format!("{indentation}{line}"));
+                        continue;
+                    };
+                    let prefix = format!("{prefix}{}", block.ty.to_upper_camel_case());
+                    lines.push(format!("{indentation}{} {{", fix_ident(&block.ty)));
+                    self.macro_body(nested, &block.body, &prefix, false, indent + 1, lines);
+                    lines.push(format!("{indentation}}}"));
+                }
+            }
+        }
+    }
+
+    /// Returns the lines of the `lifecycle` block of a resource written using the block syntax
+    /// of `tf_bindgen::codegen::resource!`.
+    fn lifecycle(&mut self, body: &Body) -> Vec<String> {
+        let mut lines = vec!["lifecycle {".to_string()];
+        for item in &body.items {
+            match item {
+                Item::Attribute(name, Expression::Bool(value))
+                    if name == "create_before_destroy" || name == "prevent_destroy" =>
+                {
+                    lines.push(format!("    {name} = {value}"));
+                }
+                Item::Attribute(name, Expression::Tuple(items)) if name == "ignore_changes" => {
+                    let attributes = items
+                        .iter()
+                        .map(|item| item.as_literal().unwrap_or_else(|| item.to_string()))
+                        .map(|attribute| format!("{attribute:?}"))
+                        .join(", ");
+                    lines.push(format!("    {name} = [{attributes}]"));
+                }
+                Item::Attribute(name, value)
+                    if name == "ignore_changes" && value.to_string().trim() == "all" =>
+                {
+                    lines.push(format!("    {name} = all"));
+                }
+                Item::Attribute(name, Expression::Tuple(items))
+                    if name == "replace_triggered_by" =>
+                {
+                    for item in items {
+                        let line = match self.object_references(item) {
+                            Some(references) => Some(format!("{name} = {}", references.join(""))),
+                            None => self.resolve(item).map(|(reference, _)| {
+                                format!("replace_triggered_by_attribute = {reference}")
+                            }),
+                        };
+                        let line = line.unwrap_or_else(|| {
+                            todo("unknown reference", &format!("{name} = [{item}]"))
+                        });
+                        lines.push(format!("    {line}"));
+                    }
+                }
+                Item::Block(block) if block.ty == "precondition" || block.ty == "postcondition" => {
+                    match condition(&block.body) {
+                        Some((condition, message)) => {
+                            lines.push(format!("    {} {{", block.ty));
+                            lines.push(format!("        condition = {condition}"));
+                            lines.push(format!("        error_message = {message}"));
+                            lines.push("    }".to_string());
+                        }
+                        None => lines.push(format!(
+                            "    {}",
+                            todo("invalid condition", &block_name(block))
+                        )),
+                    }
+                }
+                Item::Attribute(name, value) => lines.push(format!(
+                    "    {}",
+                    todo("unsupported setting", &format!("{name} = {value}"))
+                )),
+                Item::Block(block) => lines.push(format!(
+                    "    {}",
+                    todo("unsupported block", &block_name(block))
+                )),
+            }
+        }
+        lines.push("}".to_string());
+        lines
+    }
+
+    fn output(&mut self, name: &str, body: &Body) {
+        let value = match body.attribute("value") {
+            Some(value) => self.output_value(value),
+            None => {
+                self.lines
+                    .push(format!("// TODO: output `{name}` without value"));
+                return;
+            }
+        };
+        let mut options = Vec::new();
+        for item in &body.items {
+            let option = match item {
+                Item::Attribute(name, _) if name == "value" => continue,
+                Item::Attribute(name, value) => match (name.as_str(), value) {
+                    ("description", value) => value
+                        .as_literal()
+                        .map(|description| vec![format!("description = {description:?}")]),
+                    ("sensitive", Expression::Bool(value)) => {
+                        Some(vec![format!("sensitive = {value}")])
+                    }
+                    ("depends_on", value) => self.object_references(value).map(|references| {
+                        references
+                            .into_iter()
+                            .map(|reference| format!("depends_on = {reference}"))
+                            .collect()
+                    }),
+                    _ => None,
+                }
+                .ok_or_else(|| todo("unsupported setting", &format!("{name} = {value}"))),
+                Item::Block(block) => Err(todo("unsupported block", &block_name(block))),
+            };
+            match option {
+                Ok(option) => options.extend(option),
+                Err(todo) => self.lines.push(todo),
+            }
+        }
+        let lines = if options.is_empty() {
+            vec![format!("tf_bindgen::output!(stack, {name:?} = {value})")]
+        } else {
+            ["tf_bindgen::output!(".to_string(), "    stack,".to_string()]
+                .into_iter()
+                .chain([format!("    {name:?} = {value},")])
+                .chain(options.into_iter().map(|option| format!("    {option},")))
+                .chain([")".to_string()])
+                .collect()
+        };
+        self.statement("", lines);
+    }
+
+    fn output_value(&mut self, value: &Expression) -> String {
+        if let Some((reference, _)) = self.resolve(unwrap(value)) {
+            return reference;
+        }
+        match unwrap(value) {
+            value @ Expression::Template(_) => self.scalar(value, &BlockType::String),
+            Expression::Number(number) if number.parse::<i64>().is_ok() => format!("{number}_i64"),
+            value => self.scalar(value, &BlockType::Dynamic),
+        }
+    }
+
+    /// Returns the value of the attribute `name` of a block with the schema `schema`.
+    fn attribute(
+        &mut self,
+        schema: &v1_0::Block,
+        name: &str,
+        value: &Expression,
+        prefix: &str,
+    ) -> Result<String, &'static str> {
+        let attribute = schema
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.get(name))
+            .ok_or("unknown attribute")?;
+        if attribute.computed == Some(true) && attribute.optional != Some(true) {
+            return Err("read-only attribute");
+        }
+        let type_name = format!("{prefix}{}", name.to_upper_camel_case());
+        self.value(value, &attribute.r#type, &type_name)
+            .ok_or("unsupported expression")
+    }
+
+    /// Returns the builder expression of the nested `block` (e.g. of a provider).
+    fn builder(
+        &mut self,
+        schema: &v1_0::Block,
+        block: &Block,
+        prefix: &str,
+    ) -> Result<String, String> {
+        let nested = nested_block(schema, &block.ty)
+            .ok_or_else(|| todo("unknown block", &block_name(block)))?;
+        let type_name = format!("{prefix}{}", block.ty.to_upper_camel_case());
+        let mut builder = format!("{type_name}::builder()");
+        for item in &block.body.items {
+            let setter = match item {
+                Item::Attribute(name, value) => self
+                    .attribute(nested, name, value, &type_name)
+                    .map_err(|reason| todo(reason, &format!("{name} = {value}")))?,
+                Item::Block(block) => self.builder(nested, block, &type_name)?,
+            };
+            let name = match item {
+                Item::Attribute(name, _) => name,
+                Item::Block(block) => &block.ty,
+            };
+            builder += &format!(".{}({setter})", self::setter(name));
+        }
+        Ok(builder + ".build()")
+    }
+
+    /// Returns the Rust expression passed to a setter of type `ty`. `type_name` is the name of
+    /// the type generated for objects at this position.
+    fn value(&mut self, value: &Expression, ty: &BlockType, type_name: &str) -> Option<String> {
+        match ty {
+            BlockType::List(inner) | BlockType::Set(inner) => {
+                let Expression::Tuple(items) = value else {
+                    return None;
+                };
+                let items = items
+                    .iter()
+                    .filter(|item| **item != Expression::Null)
+                    .map(|item| self.value(item, inner, type_name))
+                    .collect::<Option<Vec<_>>>()?;
+                let set = matches!(ty, BlockType::Set(_));
+                let value = if items.is_empty() {
+                    let inner = rust_type(inner, type_name);
+                    match set {
+                        true => format!("tf_bindgen::value::ValueSet::<{inner}>::default()"),
+                        false => format!("Vec::<tf_bindgen::Value<{inner}>>::new()"),
+                    }
+                } else if set {
+                    format!("tf_bindgen::set![{}]", items.join(", "))
+                } else if items.iter().all(|item| is_constant(item)) {
+                    format!("[{}]", items.join(", "))
+                } else {
+                    self.imports
+                        .insert("tf_bindgen::value::IntoValue".to_string());
+                    let items = items.iter().map(|item| match item.starts_with('&') {
+                        true => format!("({item}).into_value()"),
+                        false => format!("{item}.into_value()"),
+                    });
+                    format!("[{}]", items.format(", "))
+                };
+                Some(value)
+            }
+            BlockType::Map(inner) => {
+                let Expression::Object(fields) = value else {
+                    return None;
+                };
+                if fields.is_empty() {
+                    let inner = rust_type(inner, type_name);
+                    return Some(format!(
+                        "std::collections::HashMap::<String, tf_bindgen::Value<{inner}>>::new()"
+                    ));
+                }
+                let fields = fields
+                    .iter()
+                    .filter(|(_, value)| *value != Expression::Null)
+                    .map(|(key, value)| {
+                        Some(format!(
+                            "{key:?} = {}",
+                            self.value(value, inner, type_name)?
+                        ))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("tf_bindgen::map! {{ {} }}", fields.join(", ")))
+            }
+            BlockType::Object(types) => {
+                let Expression::Object(fields) = value else {
+                    return None;
+                };
+                let mut builder = format!("{type_name}::builder()");
+                for (name, value) in fields {
+                    if *value == Expression::Null {
+                        continue;
+                    }
+                    let field_type = format!("{type_name}{}", name.to_upper_camel_case());
+                    let value = self.value(value, types.get(name)?, &field_type)?;
+                    builder += &format!(".{}({value})", setter(name));
+                }
+                Some(builder + ".build()")
+            }
+            _ => Some(self.scalar(value, ty)),
+        }
+    }
+
+    /// Returns the Rust expression of a value of the primitive type `ty`. Expressions without
+    /// Rust equivalent are passed to Terraform as is.
+    fn scalar(&mut self, value: &Expression, ty: &BlockType) -> String {
+        match (unwrap(value), ty) {
+            (value @ Expression::Template(parts), ty) => match (value.as_literal(), ty) {
+                (Some(text), BlockType::String) => return format!("{text:?}"),
+                (Some(text), BlockType::Number) if text.parse::<i64>().is_ok() => return text,
+                (Some(text), BlockType::Bool) if text == "true" || text == "false" => return text,
+                (Some(text), BlockType::Dynamic) => {
+                    return format!("tf_bindgen::json::json!({text:?})")
+                }
+                (None, BlockType::String) => {
+                    if let Some(template) = self.interpolate(parts) {
+                        return template;
+                    }
+                }
+                _ => {}
+            },
+            (Expression::Number(number), BlockType::Number) if number.parse::<i64>().is_ok() => {
+                return number.clone()
+            }
+            (Expression::Number(number), BlockType::String) => return format!("{number:?}"),
+            (Expression::Bool(value), BlockType::Bool) => return value.to_string(),
+            (Expression::Bool(value), BlockType::String) => return format!("\"{value}\""),
+            (value @ Expression::Traversal(..), ty) => {
+                if let Some(reference) = self.reference(value, ty) {
+                    return reference;
+                }
+            }
+            (value, BlockType::Dynamic) => {
+                if let Some(value) = json(value) {
+                    return format!("tf_bindgen::json::json!({value})");
+                }
+            }
+            _ => {}
+        }
+        raw(value, ty)
+    }
+
+    /// Returns a call of `tf_bindgen::interpolate!` creating the template `parts`.
+    fn interpolate(&mut self, parts: &[Part]) -> Option<String> {
+        let mut format = String::new();
+        let mut args = Vec::new();
+        for part in parts {
+            let text = match part {
+                Part::Literal(text) => text.clone(),
+                Part::Interpolation(Expression::Number(number)) => number.clone(),
+                Part::Interpolation(Expression::Bool(value)) => value.to_string(),
+                Part::Interpolation(value) => match value.as_literal() {
+                    Some(text) => text,
+                    None => {
+                        let arg = self
+                            .resolve(value)
+                            .map(|(reference, _)| reference)
+                            .unwrap_or_else(|| raw(value, &BlockType::String));
+                        args.push(arg);
+                        format += "{}";
+                        continue;
+                    }
+                },
+                Part::Directive(_) => return None,
+            };
+            format += &text.replace('{', "{{").replace('}', "}}");
+        }
+        let args = args.iter().map(|arg| format!(", {arg}")).join("");
+        Some(format!("tf_bindgen::interpolate!({format:?}{args})"))
+    }
+
+    /// Returns the reference to a value of type `ty`.
+    fn reference(&self, value: &Expression, ty: &BlockType) -> Option<String> {
+        let (reference, actual) = self.resolve(value)?;
+        match (actual, ty) {
+            (BlockType::String, BlockType::String)
+            | (BlockType::Number, BlockType::Number)
+            | (BlockType::Bool, BlockType::Bool)
+            | (BlockType::Dynamic, BlockType::Dynamic) => Some(reference),
+            (_, BlockType::String) => {
+                Some(format!("tf_bindgen::interpolate!(\"{{}}\", {reference})"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolve a reference to a primitive value defined by this configuration. Returns the
+    /// Rust expression of the reference and the type of the referenced value.
+    fn resolve(&self, value: &Expression) -> Option<(String, BlockType)> {
+        let Expression::Traversal(root, steps) = unwrap(value) else {
+            return None;
+        };
+        let (key, used) = key(root, steps)?;
+        let steps = &steps[used..];
+        let (ident, mut schema) = match self.bindings.get(&key)? {
+            Binding::Value(ident, ty) if steps.is_empty() => {
+                return Some((format!("&{ident}"), ty.clone()))
+            }
+            Binding::Object(ident, schema) => (ident, *schema),
+            _ => return None,
+        };
+        let mut reference = format!("&{ident}");
+        let mut steps = steps.iter();
+        loop {
+            let Some(Step::Attribute(name)) = steps.next() else {
+                return None;
+            };
+            reference += &format!(".{}", fix_ident(name));
+            let attribute = schema
+                .attributes
+                .as_ref()
+                .and_then(|attributes| attributes.get(name));
+            if let Some(attribute) = attribute {
+                return match (&attribute.r#type, steps.next()) {
+                    (
+                        ty @ (BlockType::String
+                        | BlockType::Number
+                        | BlockType::Bool
+                        | BlockType::Dynamic),
+                        None,
+                    ) => Some((reference, ty.clone())),
+                    _ => None,
+                };
+            }
+            match schema.block_types.as_ref()?.get(name)? {
+                Type::List { block, .. } => match steps.next() {
+                    Some(Step::Index(Expression::Number(index))) => {
+                        reference += &format!("[{}]", index.parse::<usize>().ok()?);
+                        schema = block;
+                    }
+                    _ => return None,
+                },
+                Type::Single { .. } => return None,
+            }
+        }
+    }
+
+    /// Returns references to the objects of the list `value` (e.g. of `depends_on`).
+    fn object_references(&self, value: &Expression) -> Option<Vec<String>> {
+        let items = match value {
+            Expression::Tuple(items) => items.as_slice(),
+            value => std::slice::from_ref(value),
+        };
+        items
+            .iter()
+            .map(|item| {
+                let Expression::Traversal(root, steps) = unwrap(item) else {
+                    return None;
+                };
+                let (key, used) = key(root, steps)?;
+                match self.bindings.get(&key)? {
+                    Binding::Object(ident, _) if used == steps.len() => Some(format!("&*{ident}")),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn provider_reference(&self, value: &Expression) -> Option<String> {
+        let Expression::Traversal(name, steps) = unwrap(value) else {
+            return None;
+        };
+        match self.bindings.get(&provider_key(name, steps)?)? {
+            Binding::Provider(ident) => Some(format!("&*{ident}")),
+            _ => None,
+        }
+    }
+
+    fn todo(&mut self, reason: &str, what: &str) {
+        self.lines.push(todo(reason, what));
+    }
+
+    fn finish(self) -> String {
+        let mut source = String::from("use std::rc::Rc;\n\n");
+        let imports = self.imports.iter().map(String::as_str);
+        for import in imports.chain(["tf_bindgen::Stack"]).sorted() {
+            source += &format!("use {import};\n");
+        }
+        source += &format!(
+            "\npub fn {}(stack: &Rc<Stack>) {{\n",
+            self.importer.function_name
+        );
+        // references replaced by TODO comments may leave variables unused
+        let unused: Vec<_> = self
+            .idents
+            .values()
+            .filter(|ident| !self.lines.iter().any(|line| is_referenced(line, ident)))
+            .map(|ident| format!("let {ident} = "))
+            .collect();
+        for line in &self.lines {
+            let line = unused
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix.as_str()))
+                .unwrap_or(line);
+            source += &format!("    {line}\n");
+        }
+        source + "}\n"
+    }
+}
+
+/// Returns `true` if `line` is code referencing the local variable `ident`.
+fn is_referenced(line: &str, ident: &str) -> bool {
+    if line.trim_start().starts_with("//") {
+        return false;
+    }
+    line.match_indices(ident).any(|(index, _)| {
+        let before = line[..index].ends_with('&') || line[..index].ends_with("&*");
+        let after = line[index + ident.len()..].chars().next();
+        before && !after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+/// Unwrap templates consisting of a single interpolation (e.g. `"${var.name}"`).
+fn unwrap(value: &Expression) -> &Expression {
+    match value {
+        Expression::Template(parts) => match parts.as_slice() {
+            [Part::Interpolation(inner)] => unwrap(inner),
+            _ => value,
+        },
+        value => value,
+    }
+}
+
+fn nested_block<'s>(schema: &'s v1_0::Block, name: &str) -> Option<&'s v1_0::Block> {
+    match schema.block_types.as_ref()?.get(name)? {
+        Type::Single { block } | Type::List { block, .. } => Some(block),
+    }
+}
+
+/// Returns the name of the setter of the field `name`.
+fn setter(name: &str) -> &str {
+    match name {
+        "build" => "build_",
+        name => fix_ident(name),
+    }
+}
+
+/// Returns the Rust type used for values of type `ty`.
+fn rust_type(ty: &BlockType, type_name: &str) -> String {
+    match ty {
+        BlockType::String => "String".to_string(),
+        BlockType::Number => "i64".to_string(),
+        BlockType::Bool => "bool".to_string(),
+        BlockType::Object(_) => type_name.to_string(),
+        _ => "tf_bindgen::json::Value".to_string(),
+    }
+}
+
+/// Returns `true` if the Rust expression `value` is not wrapped inside of a `tf_bindgen::Value`.
+fn is_constant(value: &str) -> bool {
+    !value.starts_with('&')
+        && !value.starts_with("tf_bindgen::Value")
+        && !value.starts_with("tf_bindgen::interpolate!")
+}
+
+/// Returns the expression `value` as argument of `tf_bindgen::json::json!` if it is a literal.
+fn json(value: &Expression) -> Option<String> {
+    let json = match value {
+        Expression::Null => "null".to_string(),
+        Expression::Bool(value) => value.to_string(),
+        Expression::Number(number) => number.clone(),
+        Expression::Template(_) => format!("{:?}", value.as_literal()?),
+        Expression::Tuple(items) => {
+            let items = items.iter().map(json).collect::<Option<Vec<_>>>()?;
+            format!("[{}]", items.join(", "))
+        }
+        Expression::Object(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(key, value)| Some(format!("{key:?}: {}", json(value)?)))
+                .collect::<Option<Vec<_>>>()?;
+            format!("{{ {} }}", fields.join(", "))
+        }
+        Expression::Traversal(..) | Expression::Raw(_) => return None,
+    };
+    Some(json)
+}
+
+/// Returns the literal `value` as value of a variable of type `ty`.
+fn literal(value: &Expression, ty: &BlockType) -> Option<String> {
+    match (value, ty) {
+        (value @ Expression::Template(_), BlockType::String) => {
+            Some(format!("{:?}", value.as_literal()?))
+        }
+        (Expression::Number(number), BlockType::Number) => Some(number.clone()),
+        (Expression::Bool(value), BlockType::Bool) => Some(value.to_string()),
+        (value, BlockType::Dynamic) => Some(format!("tf_bindgen::json::json!({})", json(value)?)),
+        _ => None,
+    }
+}
+
+/// Returns `value` passed to Terraform as is.
+fn raw(value: &Expression, ty: &BlockType) -> String {
+    raw_expression(value, &rust_type(ty, "tf_bindgen::json::Value"))
+}
+
+/// Returns `value` passed to Terraform as is using a `tf_bindgen::Value` of the Rust type `ty`.
+fn raw_expression(value: &Expression, ty: &str) -> String {
+    let expression = match value {
+        Expression::Template(parts) => template(parts),
+        value => format!("${{{value}}}"),
+    };
+    format!("tf_bindgen::Value::<{ty}>::raw_expression({expression:?})")
+}
+
+/// Returns the value of the `for_each` meta-argument, which is passed to Terraform as is
+/// because variables are not typed as sets or maps.
+fn for_each(value: &Expression) -> String {
+    let ty = match unwrap(value) {
+        Expression::Object(_) => "std::collections::BTreeMap<String, tf_bindgen::json::Value>",
+        _ => "tf_bindgen::value::ValueSet<String>",
+    };
+    raw_expression(value, ty)
+}
+
+/// Returns the unquoted text of a template.
+fn template(parts: &[Part]) -> String {
+    parts
+        .iter()
+        .map(|part| match part {
+            Part::Literal(text) => text.replace("${", "$${").replace("%{", "%%{"),
+            Part::Interpolation(value) => format!("${{{value}}}"),
+            Part::Directive(directive) => directive.clone(),
+        })
+        .collect()
+}
+
+/// Returns the condition and error message of a `validation`, `precondition` or
+/// `postcondition` block as Rust string literals.
+fn condition(body: &Body) -> Option<(String, String)> {
+    let condition = unwrap(body.attribute("condition")?).to_string();
+    let message = match body.attribute("error_message")? {
+        message @ Expression::Template(parts) => {
+            message.as_literal().unwrap_or_else(|| template(parts))
+        }
+        message => format!("${{{message}}}"),
+    };
+    Some((format!("{condition:?}"), format!("{message:?}")))
+}
+
+fn block_name(block: &Block) -> String {
+    let labels = block
+        .labels
+        .iter()
+        .map(|label| format!(" {label:?}"))
+        .join("");
+    format!("{}{labels}", block.ty)
+}
+
+/// Returns a comment marking `what` as not converted.
+fn todo(reason: &str, what: &str) -> String {
+    format!("// TODO: {reason}: {}", what.replace('\n', " "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::kubernetes;
+    use super::super::Importer;
+
+    #[test]
+    fn import_references() {
+        let (schema, providers) = kubernetes();
+        let code = Importer::new(&schema, &providers)
+            .import_hcl(
+                r#"
+                resource "kubernetes_pod" "copy" {
+                  metadata {
+                    name = "${kubernetes_pod.nginx.metadata[0].name}-copy"
+                  }
+                  replicas = max(var.replicas, 1)
+                }
+                resource "kubernetes_pod" "nginx" {
+                  metadata {
+                    name = "nginx"
+                  }
+                  replicas = var.replicas
+                }
+                variable "replicas" {
+                  default = 2
+                }
+                "#,
+            )
+            .unwrap();
+        let expected = r#"use std::rc::Rc;
+
+use crate::terraform::kubernetes::resource::kubernetes_pod;
+use tf_bindgen::Stack;
+
+pub fn build(stack: &Rc<Stack>) {
+    let replicas = stack
+        .variable::<i64>("replicas")
+        .default(2)
+        .build();
+    let nginx = tf_bindgen::codegen::resource! {
+        stack,
+        resource "kubernetes_pod" "nginx" {
+            metadata {
+                name = "nginx"
+            }
+            replicas = &replicas
+        }
+    };
+    tf_bindgen::codegen::resource! {
+        stack,
+        resource "kubernetes_pod" "copy" {
+            metadata {
+                name = tf_bindgen::interpolate!("{}-copy", &nginx.metadata[0].name)
+            }
+            replicas = tf_bindgen::Value::<i64>::raw_expression("${max(var.replicas, 1)}")
+        }
+    };
+}
+"#;
+        assert_eq!(code, expected);
+    }
+}
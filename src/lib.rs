@@ -1,16 +1,25 @@
 pub mod builder;
+pub mod cache;
 pub mod codegen;
 pub mod config;
+pub mod filter;
+pub mod import;
 pub mod macros;
 pub mod model;
-pub mod value;
+pub mod report;
+pub mod version;
 
-use std::collections::HashMap;
-
-use codegen::Generator;
-use semver::VersionReq;
+use codegen::struct_info::StructInfo;
+use codegen::{format_tokens, Generator};
+use config::ProviderConfig;
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use report::{ProviderReport, Report};
 use tf_bindgen_schema::provider;
 
+use std::path::{Component, Path as StdPath};
+
 pub use tf_bindgen_core::*;
 
 pub use crate::builder::Builder;
@@ -19,71 +28,231 @@ pub use serde_json as json;
 pub use tf_bindgen_cli as cli;
 pub use tf_bindgen_schema as schema;
 
-pub use value::Value;
-
 pub struct Bindings {
-    version: HashMap<String, VersionReq>,
+    providers: Vec<ProviderConfig>,
     schema: provider::Schema,
+    feature_gates: bool,
+}
+
+/// Used to collect the cargo features of generated modules. See [`Builder::feature_gates`].
+struct Features {
+    enabled: bool,
+    table: String,
 }
-use std::path::Path as StdPath;
 
 impl Bindings {
+    /// Returns the provider schema used to generate these bindings.
+    pub fn schema(&self) -> &provider::Schema {
+        &self.schema
+    }
+
+    /// Returns an importer converting existing Terraform configurations into Rust code using
+    /// these bindings. See [`import::Importer`].
+    pub fn importer(&self) -> import::Importer<'_> {
+        import::Importer::new(&self.schema, &self.providers)
+    }
+
+    /// Write the generated bindings to `base_path`. Every provider is written to its own
+    /// directory (`provider/<name>`) and declared in `root_file`. Returns a summary of the
+    /// generated code.
+    ///
+    /// The provider modules are declared using paths relative to `root_file`. Thus, the
+    /// generated files can be moved or vendored as a whole. Note that `root_file` must be
+    /// declared as a module (e.g. `mod terraform;`) and cannot be included using `include!`. Use
+    /// [`Bindings::write_to_single_file`] instead.
+    ///
+    /// If feature gates are enabled (see [`Builder::feature_gates`]), every resource and data
+    /// source module is only compiled if the feature of the same name (prefixed with `data-`
+    /// for data sources) is enabled. The matching `[features]` table is written to
+    /// `features.toml` inside of `base_path`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a file cannot be written or `root_file` is not a relative path inside
+    /// of `base_path`.
     pub fn write_to_file(
         self,
         base_path: impl AsRef<StdPath>,
         root_file: impl AsRef<StdPath>,
-    ) -> std::io::Result<()> {
-        let provider_dir = base_path.as_ref().join("provider");
-        std::fs::create_dir_all(&provider_dir)?;
-
-        let result = Generator::from_schema(self.schema, self.version);
-        let mut root_content = String::new();
-        for provider in result.providers {
-            let name = &provider.provider.name();
-            let name = name.split('/').last().unwrap();
-            let provider_dir = provider_dir.join(name);
-            let resource_dir = provider_dir.join("resource");
-            std::fs::create_dir_all(&resource_dir)?;
-            let data_dir = provider_dir.join("data");
-            std::fs::create_dir_all(&data_dir)?;
-
-            let resources: String = provider
-                .resources
-                .iter()
-                .map(|construct| {
-                    let filename = format!("{}.rs", construct.ty());
-                    let path = resource_dir.join(filename);
-                    std::fs::write(path, construct.gen_rust())?;
-                    Ok(construct.ty())
-                })
-                .map(|name: std::io::Result<_>| Ok(format!("pub mod {};\n", name?)))
-                .collect::<std::io::Result<_>>()?;
-            let data_sources: String = provider
-                .data_sources
-                .iter()
-                .map(|construct| {
-                    let filename = format!("{}.rs", construct.ty());
-                    let path = data_dir.join(filename);
-                    std::fs::write(path, construct.gen_rust())?;
-                    Ok(construct.ty())
-                })
-                .map(|name: std::io::Result<_>| Ok(format!("pub mod {};\n", name?)))
-                .collect::<std::io::Result<_>>()?;
-            let content = provider.provider.gen_rust()
-                + "pub mod resource {\n"
-                + &resources
-                + "}\npub mod data {\n"
-                + &data_sources
-                + "}";
-
-            let provider_file = provider_dir.join("mod.rs");
-            std::fs::write(provider_file, content)?;
-            root_content += &format!(
-                "#[path = \"{}/mod.rs\"]\npub mod {name};\n",
-                provider_dir.to_str().unwrap()
-            )
+    ) -> std::io::Result<Report> {
+        let base_path = base_path.as_ref();
+        let root_file = root_file.as_ref();
+        let mut prefix = String::new();
+        for component in root_file.parent().into_iter().flat_map(StdPath::components) {
+            match component {
+                Component::Normal(_) => prefix += "../",
+                Component::CurDir => {}
+                _ => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "root file must be a relative path inside of the base path",
+                    ))
+                }
+            }
+        }
+
+        let mut features = Features::new(self.feature_gates);
+        let mut root_modules = Vec::new();
+        let mut report = Report::default();
+        for provider in Generator::from_schema(self.schema, &self.providers).providers {
+            let name = &provider.module_name;
+            let mut provider_report = ProviderReport::new(&provider);
+            let provider_dir = base_path.join("provider").join(name);
+            for kind in ["resource", "data"] {
+                std::fs::create_dir_all(provider_dir.join(kind))?;
+            }
+            let module = provider_module(&provider, &features, |kind, construct| {
+                let path = provider_dir
+                    .join(kind)
+                    .join(format!("{}.rs", construct.name()));
+                let source = construct.gen_rust();
+                provider_report.add_source(&source);
+                std::fs::write(path, source)?;
+                let module = format_ident!("{}", construct.name());
+                Ok(quote!(pub mod #module;))
+            })?;
+            let content = format_tokens(module);
+            provider_report.add_source(&content);
+            std::fs::write(provider_dir.join("mod.rs"), content)?;
+            features.add_provider(&provider);
+            report.providers.push(provider_report);
+
+            let path = format!("{prefix}provider/{name}/mod.rs");
+            let module = format_ident!("{name}");
+            root_modules.push(quote! {
+                #[path = #path]
+                pub mod #module;
+            });
+        }
+        std::fs::write(
+            base_path.join(root_file),
+            format_tokens(quote!(#(#root_modules)*)),
+        )?;
+        features.write_to_file(base_path.join("features.toml"))?;
+        Ok(report)
+    }
+
+    /// Write the generated bindings of all providers into the single file `path`. Intended to
+    /// be included using `include!` (e.g. from a build script's `OUT_DIR`). Returns a summary
+    /// of the generated code.
+    ///
+    /// If feature gates are enabled, the `[features]` table is written to `features.toml` next
+    /// to `path`. See [`Bindings::write_to_file`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `path` or `features.toml` cannot be written.
+    pub fn write_to_single_file(self, path: impl AsRef<StdPath>) -> std::io::Result<Report> {
+        let path = path.as_ref();
+        let mut features = Features::new(self.feature_gates);
+        let mut content = String::new();
+        let mut report = Report::default();
+        for provider in Generator::from_schema(self.schema, &self.providers).providers {
+            let name = &provider.module_name;
+            let mut provider_report = ProviderReport::new(&provider);
+            let module = provider_module(&provider, &features, |_, construct| {
+                let module = format_ident!("{}", construct.name());
+                let source = construct.gen_tokens();
+                Ok(quote!(pub mod #module { #source }))
+            })?;
+            let module_name = format_ident!("{name}");
+            let source = format_tokens(quote!(pub mod #module_name { #module }));
+            provider_report.add_source(&source);
+            content += &source;
+            features.add_provider(&provider);
+            report.providers.push(provider_report);
+        }
+        std::fs::write(path, content)?;
+        features.write_to_file(path.with_file_name("features.toml"))?;
+        Ok(report)
+    }
+}
+
+/// Generate the module of `provider`. The resource and data source modules are generated using
+/// `module`, which is called with the kind of module (`resource` or `data`) and the construct.
+fn provider_module(
+    provider: &codegen::Provider,
+    features: &Features,
+    mut module: impl FnMut(&str, &StructInfo) -> std::io::Result<TokenStream>,
+) -> std::io::Result<TokenStream> {
+    let mut gen_modules = |kind: &str, constructs: &[StructInfo]| {
+        constructs
+            .iter()
+            .map(|construct| {
+                let gate = features.gate(&Features::name(kind, construct));
+                let module = module(kind, construct)?;
+                Ok(quote!(#gate #module))
+            })
+            .collect::<std::io::Result<Vec<_>>>()
+    };
+    let resources = gen_modules("resource", &provider.resources)?;
+    let data_sources = gen_modules("data", &provider.data_sources)?;
+    let provider = provider.provider.gen_tokens();
+    Ok(quote! {
+        #provider
+        pub mod resource {
+            #(#resources)*
+        }
+        pub mod data {
+            #(#data_sources)*
+        }
+    })
+}
+
+impl Features {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            table: String::new(),
+        }
+    }
+
+    /// Returns the name of the feature used to gate `construct`.
+    fn name(kind: &str, construct: &StructInfo) -> String {
+        match kind {
+            "data" => format!("data-{}", construct.ty()),
+            _ => construct.ty().to_string(),
+        }
+    }
+
+    /// Returns the attribute used to gate a module behind `feature`.
+    fn gate(&self, feature: &str) -> TokenStream {
+        if self.enabled {
+            quote!(#[cfg(feature = #feature)])
+        } else {
+            TokenStream::new()
+        }
+    }
+
+    /// Add the features of `provider` to the `[features]` table. The feature named after the
+    /// provider module will enable all of them.
+    fn add_provider(&mut self, provider: &codegen::Provider) {
+        if !self.enabled {
+            return;
+        }
+        let resources = provider
+            .resources
+            .iter()
+            .map(|construct| Features::name("resource", construct));
+        let data_sources = provider
+            .data_sources
+            .iter()
+            .map(|construct| Features::name("data", construct));
+        let features: Vec<_> = resources.chain(data_sources).sorted().collect();
+        let all = features
+            .iter()
+            .map(|feature| format!("\"{feature}\""))
+            .join(", ");
+        self.table += &format!("{} = [{all}]\n", provider.module_name);
+        for feature in features {
+            self.table += &format!("{feature} = []\n");
+        }
+    }
+
+    fn write_to_file(&self, path: impl AsRef<StdPath>) -> std::io::Result<()> {
+        if !self.enabled {
+            return Ok(());
         }
-        let root_file = base_path.as_ref().join(root_file);
-        std::fs::write(root_file, root_content)
+        std::fs::write(path, format!("[features]\n{}", self.table))
     }
 }
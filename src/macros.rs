@@ -4,7 +4,7 @@ macro_rules! set {
 		{
 			#[allow(dead_code)]
 			use $crate::value::IntoValue;
-			let mut set = ::std::collections::HashSet::<$crate::Value<_>>::new();
+			let mut set = $crate::value::ValueSet::<_>::default();
 			$(
 				let value = $value;
 				set.insert(value.into_value());
@@ -29,11 +29,63 @@ macro_rules! map {
 	};
 }
 
+/// Used to add a Terraform output to the stack of a scope. The value can be anything
+/// implementing [`crate::value::IntoValue`], e.g. a literal or a reference to a resource field.
+/// Additional arguments will be passed to the corresponding setter of
+/// [`crate::OutputBuilder`].
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// tf_bindgen::output!(&stack, "pod_name" = &pod.metadata[0].name);
+/// tf_bindgen::output!(
+///     &stack,
+///     "pod_uid" = &pod.metadata[0].uid,
+///     description = "UID of the nginx pod",
+///     sensitive = true
+/// );
+/// ```
+#[macro_export]
+macro_rules! output {
+    ($scope:expr, $name:literal = $value:expr $(, $option:ident = $arg:expr)*$(,)?) => {
+		{
+			use $crate::Scope;
+			use $crate::value::IntoValue;
+			let value: $crate::Value<_> = $value.into_value();
+			$scope.stack()
+				.add_output($name, value)
+				$( .$option($arg) )*
+				.build()
+		}
+	};
+}
+
+/// Used to create a string template embedding references (e.g. `${var.name}`) into literal
+/// text. Placeholders use the syntax of [`format!`] (i.e. `{}` and `{<index>}`), but arguments
+/// can be anything implementing [`crate::value::ToExpression`]. Template sequences in literal
+/// text (i.e. `${` and `%{`) will be escaped.
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let name = tf_bindgen::interpolate!("{}-nginx", &pod.metadata[0].name);
+/// ```
+#[macro_export]
+macro_rules! interpolate {
+    ($format:literal $(, $arg:expr)*$(,)?) => {
+		$crate::value::interpolate(
+			$format,
+			&[$( &$arg as &dyn $crate::value::ToExpression ),*]
+		)
+	};
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::{HashMap, HashSet};
+    use std::collections::HashMap;
 
-    use crate::Value;
+    use crate::value::{Cell, Computed, IntoValue, ValueSet};
+    use crate::{Stack, Value};
 
     #[test]
     pub fn map_single() {
@@ -59,14 +111,14 @@ mod tests {
 
     #[test]
     pub fn set_single() {
-        let _map: HashSet<Value<String>> = crate::set! {
+        let _map: ValueSet<String> = crate::set! {
             "value"
         };
     }
 
     #[test]
     pub fn set_multi_value() {
-        let _map: HashSet<Value<String>> = crate::set! {
+        let _map: ValueSet<String> = crate::set! {
             "value",
             "value2"
         };
@@ -74,8 +126,50 @@ mod tests {
 
     #[test]
     pub fn set_trailing_comma() {
-        let _map: HashSet<Value<String>> = crate::set! {
+        let _map: ValueSet<String> = crate::set! {
             "value",
         };
     }
+
+    #[test]
+    pub fn output_reference() {
+        let stack = Stack::new("test");
+        let cell: Cell<Computed<Value<String>>> =
+            Cell::new("kubernetes_pod.nginx.id", Computed::default());
+        crate::output!(&stack, "pod_id" = &cell);
+        let document = stack.to_document();
+        assert_eq!(
+            document.output["pod_id"].value,
+            "${kubernetes_pod.nginx.id}"
+        );
+        assert_eq!(document.meta.outputs["pod_id"], "pod_id");
+    }
+
+    #[test]
+    pub fn output_options() {
+        let stack = Stack::new("test");
+        let cell: Cell<Value<String>> =
+            Cell::new("kubernetes_pod.nginx.name", "nginx".into_value());
+        crate::output!(
+            &stack,
+            "pod_name" = &cell,
+            description = "name of the pod",
+            sensitive = true,
+        );
+        let document = stack.to_document();
+        let output = &document.output["pod_name"];
+        assert_eq!(output.value, "${kubernetes_pod.nginx.name}");
+        assert_eq!(output.description.as_deref(), Some("name of the pod"));
+        assert_eq!(output.sensitive, Some(true));
+    }
+
+    #[test]
+    pub fn interpolate_reference() {
+        let stack = Stack::new("test");
+        let namespace = stack.variable::<String>("namespace").build();
+        let name: Cell<Value<String>> = Cell::new("kubernetes_pod.nginx.id", "id".into_value());
+        let value = crate::interpolate!("{}/{}-%{{x}}", namespace, &name);
+        let value = serde_json::to_value(value).unwrap();
+        assert_eq!(value, "${var.namespace}/${kubernetes_pod.nginx.id}-%%{x}");
+    }
 }
@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+
+use crate::codegen::struct_info::StructInfo;
+use crate::codegen::Provider;
+
+/// Used to summarize the generated bindings. See [`crate::Bindings::write_to_file`].
+#[derive(Debug, Default)]
+pub struct Report {
+    pub providers: Vec<ProviderReport>,
+}
+
+#[derive(Debug, Default)]
+pub struct ProviderReport {
+    /// Name of the generated provider module.
+    pub name: String,
+    pub resources: usize,
+    pub data_sources: usize,
+    /// Number of generated structs (including builders and nested types).
+    pub structs: usize,
+    /// Number of generated lines of code.
+    pub lines: usize,
+}
+
+impl ProviderReport {
+    /// Create the report of the generated `provider`. Generated lines are added using
+    /// [`ProviderReport::add_source`].
+    pub(crate) fn new(provider: &Provider) -> Self {
+        let constructs = provider.resources.iter().chain(&provider.data_sources);
+        Self {
+            name: provider.module_name.clone(),
+            resources: provider.resources.len(),
+            data_sources: provider.data_sources.len(),
+            structs: provider.provider.struct_count()
+                + constructs.map(StructInfo::struct_count).sum::<usize>(),
+            lines: 0,
+        }
+    }
+
+    /// Add the generated `source` to this report.
+    pub(crate) fn add_source(&mut self, source: &str) {
+        self.lines += source.lines().count();
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for provider in &self.providers {
+            writeln!(
+                f,
+                "{}: {} resources, {} data sources, {} structs, {} lines",
+                provider.name,
+                provider.resources,
+                provider.data_sources,
+                provider.structs,
+                provider.lines
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProviderReport, Report};
+
+    #[test]
+    fn provider_report() {
+        let mut provider = ProviderReport {
+            name: "kubernetes".to_string(),
+            resources: 1,
+            structs: 2,
+            ..Default::default()
+        };
+        provider.add_source("pub struct Pod {\n}\npub struct PodBuilder {\n}\n");
+        let report = Report {
+            providers: vec![provider],
+        };
+        assert_eq!(
+            report.to_string(),
+            "kubernetes: 1 resources, 0 data sources, 2 structs, 4 lines\n"
+        );
+    }
+}
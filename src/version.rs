@@ -0,0 +1,342 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use itertools::Itertools;
+use semver::{Comparator, Op, Prerelease, VersionReq};
+
+/// Used to store the version constraint of a provider. Constraints can be specified using
+/// Cargo's syntax (e.g. `2.17` or `>=1.0, <3`) or Terraform's syntax (e.g. `~> 2.17` or
+/// `!= 2.1.0`) and will be translated to Terraform's syntax. See
+/// [`VersionConstraint::parse`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionConstraint {
+    /// Constraint as specified by the user.
+    text: String,
+    constraints: Vec<Constraint>,
+}
+
+/// A single Terraform version constraint (e.g. `>= 1.2.0`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Constraint {
+    op: Operator,
+    version: Version,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Pessimistic,
+}
+
+/// Version used inside of Terraform constraints. Minor and patch version are optional
+/// because they change the meaning of `~>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Version {
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Prerelease,
+}
+
+impl VersionConstraint {
+    /// Parse `text` using Cargo's syntax. Falls back to Terraform's syntax if `text` is not a
+    /// valid Cargo version requirement. Note that both syntaxes interpret a bare version
+    /// differently: `2.17` is read as Cargo's `^2.17`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `text` is neither a valid Cargo nor Terraform constraint.
+    pub fn parse(text: &str) -> Result<Self> {
+        match VersionReq::parse(text) {
+            Ok(req) => Ok(Self {
+                text: text.to_string(),
+                ..Self::from_cargo(&req)?
+            }),
+            Err(_) => Self::from_terraform(text),
+        }
+    }
+
+    /// Translate a Cargo version requirement.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `req` uses an unsupported operator.
+    pub fn from_cargo(req: &VersionReq) -> Result<Self> {
+        let constraints = req
+            .comparators
+            .iter()
+            .map(translate)
+            .flatten_ok()
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            text: req.to_string(),
+            constraints,
+        })
+    }
+
+    /// Parse a constraint using Terraform's syntax (e.g. `>= 1.2.0, < 2.0.0`).
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `text` is not a valid Terraform version constraint.
+    pub fn from_terraform(text: &str) -> Result<Self> {
+        let constraints = text
+            .split(',')
+            .map(|constraint| Constraint::parse(constraint.trim()))
+            .collect::<Result<_>>()
+            .with_context(|| format!("invalid version constraint `{text}`"))?;
+        Ok(Self {
+            text: text.to_string(),
+            constraints,
+        })
+    }
+
+    /// Returns the constraint using Terraform's syntax.
+    pub fn to_terraform(&self) -> String {
+        if self.constraints.is_empty() {
+            return ">= 0.0.0".to_string();
+        }
+        self.constraints.iter().join(", ")
+    }
+
+    /// Returns the constraint as specified by the user.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl FromStr for VersionConstraint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<VersionReq> for VersionConstraint {
+    type Error = anyhow::Error;
+
+    fn try_from(value: VersionReq) -> Result<Self> {
+        Self::from_cargo(&value)
+    }
+}
+
+impl Display for VersionConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Translate a single Cargo comparator to Terraform constraints.
+fn translate(comparator: &Comparator) -> Result<Vec<Constraint>> {
+    let Comparator {
+        major,
+        minor,
+        patch,
+        ..
+    } = *comparator;
+    let pre = &comparator.pre;
+    let lower = Constraint::new(
+        Operator::GreaterEqual,
+        Version::new(major, minor.unwrap_or(0), patch.unwrap_or(0), pre),
+    );
+    let less = |major, minor, patch| {
+        Constraint::new(
+            Operator::Less,
+            Version::new(major, minor, patch, &Prerelease::EMPTY),
+        )
+    };
+    // Upper bound of a partial version (e.g. `1.2` will match `1.2.*`).
+    let partial_upper = match minor {
+        Some(minor) => less(major, minor + 1, 0),
+        None => less(major + 1, 0, 0),
+    };
+    let constraints = match comparator.op {
+        Op::Exact => match (minor, patch) {
+            (Some(minor), Some(patch)) => vec![Constraint::new(
+                Operator::Equal,
+                Version::new(major, minor, patch, pre),
+            )],
+            _ => vec![lower, partial_upper],
+        },
+        Op::Greater => match (minor, patch) {
+            (Some(minor), Some(patch)) => vec![Constraint::new(
+                Operator::Greater,
+                Version::new(major, minor, patch, pre),
+            )],
+            _ => vec![Constraint {
+                op: Operator::GreaterEqual,
+                ..partial_upper
+            }],
+        },
+        Op::GreaterEq => vec![lower],
+        Op::Less => vec![Constraint {
+            op: Operator::Less,
+            ..lower
+        }],
+        Op::LessEq => match (minor, patch) {
+            (Some(minor), Some(patch)) => vec![Constraint::new(
+                Operator::LessEqual,
+                Version::new(major, minor, patch, pre),
+            )],
+            _ => vec![partial_upper],
+        },
+        Op::Tilde | Op::Wildcard => vec![lower, partial_upper],
+        Op::Caret => {
+            let upper = match (major, minor, patch) {
+                (0, None, _) => less(1, 0, 0),
+                (0, Some(0), Some(patch)) => less(0, 0, patch + 1),
+                (0, Some(minor), _) => less(0, minor + 1, 0),
+                (major, _, _) => less(major + 1, 0, 0),
+            };
+            vec![lower, upper]
+        }
+        _ => bail!("unsupported version requirement `{comparator}`"),
+    };
+    Ok(constraints)
+}
+
+impl Constraint {
+    fn new(op: Operator, version: Version) -> Self {
+        Self { op, version }
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let operators = [
+            ("~>", Operator::Pessimistic),
+            (">=", Operator::GreaterEqual),
+            ("<=", Operator::LessEqual),
+            ("!=", Operator::NotEqual),
+            (">", Operator::Greater),
+            ("<", Operator::Less),
+            ("=", Operator::Equal),
+        ];
+        let (op, version) = operators
+            .into_iter()
+            .find_map(|(prefix, op)| Some((op, text.strip_prefix(prefix)?)))
+            .unwrap_or((Operator::Equal, text));
+        Ok(Self {
+            op,
+            version: version.trim().parse()?,
+        })
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let op = match self.op {
+            Operator::Equal => "=",
+            Operator::NotEqual => "!=",
+            Operator::Greater => ">",
+            Operator::GreaterEqual => ">=",
+            Operator::Less => "<",
+            Operator::LessEqual => "<=",
+            Operator::Pessimistic => "~>",
+        };
+        write!(f, "{op} {}", self.version)
+    }
+}
+
+impl Version {
+    fn new(major: u64, minor: u64, patch: u64, pre: &Prerelease) -> Self {
+        Self {
+            major,
+            minor: Some(minor),
+            patch: Some(patch),
+            pre: pre.clone(),
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (version, pre) = match s.split_once('-') {
+            Some((version, pre)) => (version, Prerelease::new(pre)?),
+            None => (s, Prerelease::EMPTY),
+        };
+        let mut parts = version.split('.').map(|part| {
+            part.parse::<u64>()
+                .map_err(|_| anyhow!("invalid version number `{part}`"))
+        });
+        let major = parts.next().unwrap()?;
+        let minor = parts.next().transpose()?;
+        let patch = parts.next().transpose()?;
+        if parts.next().is_some() {
+            bail!("version `{s}` has too many components")
+        }
+        if !pre.is_empty() && patch.is_none() {
+            bail!("pre-release version `{s}` must specify a patch version")
+        }
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{patch}")?;
+        }
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VersionConstraint;
+
+    fn terraform(text: &str) -> String {
+        VersionConstraint::parse(text).unwrap().to_terraform()
+    }
+
+    #[test]
+    fn cargo_constraint() {
+        assert_eq!(terraform("=2.19.0"), "= 2.19.0");
+        assert_eq!(terraform("2.17"), ">= 2.17.0, < 3.0.0");
+        assert_eq!(terraform("~1.2.3"), ">= 1.2.3, < 1.3.0");
+        assert_eq!(terraform("^0.2.3"), ">= 0.2.3, < 0.3.0");
+        assert_eq!(terraform("^0.0.3"), ">= 0.0.3, < 0.0.4");
+        assert_eq!(terraform("1.*"), ">= 1.0.0, < 2.0.0");
+        assert_eq!(terraform(">1.2, <=2"), ">= 1.3.0, < 3.0.0");
+        assert_eq!(terraform("=1.0.0-beta.1"), "= 1.0.0-beta.1");
+        assert_eq!(terraform("*"), ">= 0.0.0");
+    }
+
+    #[test]
+    fn terraform_constraint() {
+        assert_eq!(terraform("~> 2.17"), "~> 2.17");
+        assert_eq!(terraform(">= 1.2.0, != 1.4.1"), ">= 1.2.0, != 1.4.1");
+        assert_eq!(terraform("~>1.0.0-rc1"), "~> 1.0.0-rc1");
+        assert_eq!(
+            VersionConstraint::parse("~> 2.17").unwrap().to_string(),
+            "~> 2.17"
+        );
+    }
+
+    #[test]
+    fn invalid_constraint() {
+        assert!(VersionConstraint::parse("~> latest").is_err());
+        assert!(VersionConstraint::parse("!= 1.2.3.4").is_err());
+        assert!(VersionConstraint::parse("~> 1.0-beta").is_err());
+        assert!(VersionConstraint::parse("").is_err());
+    }
+}